@@ -0,0 +1,70 @@
+//! Compares mutexes under contention from increasing thread counts.
+//!
+//! Run with `cargo bench --bench lock_contention`.
+
+use concurrency_traits::mutex::{
+    AdaptiveMutexStd, McsLockStd, Mutex, ParkMutexStd, SpinLockStd, TicketLockStd,
+};
+use std::sync::{Arc, Barrier};
+use std::thread::spawn;
+use std::time::{Duration, Instant};
+
+const LOCKS_PER_THREAD: usize = 100_000;
+const THREAD_COUNTS: [usize; 4] = [2, 4, 8, 16];
+
+fn contend<L>(lock: L, threads: usize) -> Duration
+where
+    L: 'static + Send + Sync,
+    for<'a> L: Mutex<'a, Item = usize>,
+{
+    let lock = Arc::new(lock);
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = lock.clone();
+            let barrier = barrier.clone();
+            spawn(move || {
+                barrier.wait();
+                for _ in 0..LOCKS_PER_THREAD {
+                    *lock.lock() += 1;
+                }
+            })
+        })
+        .collect();
+    let start = Instant::now();
+    barrier.wait();
+    for handle in handles {
+        handle.join().expect("Could not join");
+    }
+    let elapsed = start.elapsed();
+    assert_eq!(*lock.lock(), threads * LOCKS_PER_THREAD);
+    elapsed
+}
+
+fn report(name: &str, threads: usize, elapsed: Duration) {
+    println!(
+        "{:<13} {:>3} threads: {:>10.3?} ({:.1} ns/lock)",
+        name,
+        threads,
+        elapsed,
+        elapsed.as_nanos() as f64 / (threads * LOCKS_PER_THREAD) as f64
+    );
+}
+
+fn main() {
+    for &threads in THREAD_COUNTS.iter() {
+        report("SpinLock", threads, contend(SpinLockStd::new(0), threads));
+        report(
+            "TicketLock",
+            threads,
+            contend(TicketLockStd::new(0), threads),
+        );
+        report("McsLock", threads, contend(McsLockStd::new(0), threads));
+        report("ParkMutex", threads, contend(ParkMutexStd::new(0), threads));
+        report(
+            "AdaptiveMutex",
+            threads,
+            contend(AdaptiveMutexStd::new(0), threads),
+        );
+    }
+}
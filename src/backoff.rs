@@ -0,0 +1,177 @@
+//! Strategies for waiting between attempts in spinning primitives.
+
+use crate::ThreadFunctions;
+use core::fmt::{Debug, Formatter};
+use core::hint::spin_loop;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+/// A way of waiting between failed attempts to acquire a lock. A new value is
+/// created with [`Default`] for each acquire.
+pub trait Backoff: Default {
+    /// Waits after a failed attempt
+    fn backoff(&mut self);
+}
+
+/// Spins once with [`spin_loop`] each attempt.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SpinBackoff;
+impl Backoff for SpinBackoff {
+    #[inline]
+    fn backoff(&mut self) {
+        spin_loop()
+    }
+}
+
+/// Spins with [`spin_loop`] twice as long each attempt, up to `2^MAX_SHIFT`
+/// spins.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ExponentialBackoff<const MAX_SHIFT: u32 = 6> {
+    shift: u32,
+}
+impl<const MAX_SHIFT: u32> Backoff for ExponentialBackoff<MAX_SHIFT> {
+    fn backoff(&mut self) {
+        for _ in 0..1u32 << self.shift {
+            spin_loop();
+        }
+        if self.shift < MAX_SHIFT {
+            self.shift += 1;
+        }
+    }
+}
+
+/// Spins with [`spin_loop`] for `N` attempts then yields with
+/// [`ThreadFunctions::yield_now`].
+pub struct YieldAfter<CS, const N: u32> {
+    attempts: u32,
+    phantom_cs: PhantomData<fn() -> CS>,
+}
+impl<CS, const N: u32> Default for YieldAfter<CS, N> {
+    fn default() -> Self {
+        Self {
+            attempts: 0,
+            phantom_cs: PhantomData,
+        }
+    }
+}
+impl<CS, const N: u32> Debug for YieldAfter<CS, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("YieldAfter")
+            .field("attempts", &self.attempts)
+            .finish()
+    }
+}
+impl<CS, const N: u32> Backoff for YieldAfter<CS, N>
+where
+    CS: ThreadFunctions,
+{
+    fn backoff(&mut self) {
+        if self.attempts < N {
+            self.attempts += 1;
+            spin_loop();
+        } else {
+            CS::yield_now();
+        }
+    }
+}
+
+/// Spins with [`spin_loop`] for `N` attempts then sleeps for `SLEEP_MICROS`
+/// microseconds with [`ThreadFunctions::sleep`].
+pub struct SleepAfter<CS, const N: u32, const SLEEP_MICROS: u64> {
+    attempts: u32,
+    phantom_cs: PhantomData<fn() -> CS>,
+}
+impl<CS, const N: u32, const SLEEP_MICROS: u64> Default for SleepAfter<CS, N, SLEEP_MICROS> {
+    fn default() -> Self {
+        Self {
+            attempts: 0,
+            phantom_cs: PhantomData,
+        }
+    }
+}
+impl<CS, const N: u32, const SLEEP_MICROS: u64> Debug for SleepAfter<CS, N, SLEEP_MICROS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SleepAfter")
+            .field("attempts", &self.attempts)
+            .finish()
+    }
+}
+impl<CS, const N: u32, const SLEEP_MICROS: u64> Backoff for SleepAfter<CS, N, SLEEP_MICROS>
+where
+    CS: ThreadFunctions,
+{
+    fn backoff(&mut self) {
+        if self.attempts < N {
+            self.attempts += 1;
+            spin_loop();
+        } else {
+            CS::sleep(Duration::from_micros(SLEEP_MICROS));
+        }
+    }
+}
+
+/// The backoff used by spinning primitives if none is given. Yields on every
+/// failed attempt.
+pub type DefaultBackoff<CS> = YieldAfter<CS, 0>;
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::backoff::{ExponentialBackoff, SleepAfter, SpinBackoff};
+    #[cfg(feature = "std")]
+    use crate::mutex::{McsLock, Mutex, SpinLock, TicketLock};
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{ReaderPreferring, RwLock, SpinRwLock};
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn backoff_test() {
+        const THREADS: usize = 4;
+        const COUNT: usize = 1000;
+        let spin = Arc::new(SpinLock::<_, StdThreadFunctions, SpinBackoff>::new(0));
+        let exponential = Arc::new(SpinLock::<_, StdThreadFunctions, ExponentialBackoff>::new(
+            0,
+        ));
+        let ticket = Arc::new(TicketLock::<_, StdThreadFunctions, SpinBackoff>::new(0));
+        let mcs = Arc::new(McsLock::<_, StdThreadFunctions, ExponentialBackoff>::new(0));
+        let sleep = Arc::new(SpinRwLock::<
+            _,
+            StdThreadFunctions,
+            ReaderPreferring,
+            SleepAfter<StdThreadFunctions, 10, 1>,
+        >::new(0));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let spin = spin.clone();
+                let exponential = exponential.clone();
+                let ticket = ticket.clone();
+                let mcs = mcs.clone();
+                let sleep = sleep.clone();
+                spawn(move || {
+                    for _ in 0..COUNT {
+                        *spin.lock() += 1;
+                        *exponential.lock() += 1;
+                        *ticket.lock() += 1;
+                        *mcs.lock() += 1;
+                        *sleep.write() += 1;
+                        assert!(*sleep.read() > 0);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(*spin.lock(), THREADS * COUNT);
+        assert_eq!(*exponential.lock(), THREADS * COUNT);
+        assert_eq!(*ticket.lock(), THREADS * COUNT);
+        assert_eq!(*mcs.lock(), THREADS * COUNT);
+        assert_eq!(*sleep.read(), THREADS * COUNT);
+    }
+}
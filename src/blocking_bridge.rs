@@ -0,0 +1,195 @@
+//! A shared pool of threads for running blocking operations on behalf of async locks.
+
+use crate::queue::Queue;
+use crate::TryThreadSpawner;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+
+/// A job run on a [`BlockingBridge`] worker.
+pub type BridgeJob = Box<dyn FnOnce() + Send>;
+
+/// The message used for [`BlockingBridge`].
+pub enum BridgeMessage {
+    /// Runs a job
+    Job(BridgeJob),
+    /// Stops the worker that receives it
+    Stop,
+}
+impl Debug for BridgeMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BridgeMessage::Job(_) => write!(f, "Job"),
+            BridgeMessage::Stop => write!(f, "Stop"),
+        }
+    }
+}
+
+/// A handle to a set of worker threads that run blocking jobs, such as the lock requests of many
+/// [`RawCustomAsyncMutex`](crate::mutex::RawCustomAsyncMutex)s.
+/// Cloning shares the same workers, which stop once every handle has been dropped and all sent
+/// jobs have run.
+///
+/// A job blocks its worker until it finishes, so a bridge should have more workers than requests
+/// expected to wait at once.
+#[derive(Debug)]
+pub struct BlockingBridge<Q>
+where
+    Q: Queue<Item = BridgeMessage>,
+{
+    inner: Arc<BlockingBridgeInner<Q>>,
+}
+impl<Q> BlockingBridge<Q>
+where
+    Q: 'static + Queue<Item = BridgeMessage> + Send + Sync,
+{
+    /// Creates a new [`BlockingBridge`] with `workers` threads sharing a message queue.
+    pub fn new<TS>(
+        workers: usize,
+        message_queue: Q,
+    ) -> Result<(Self, Vec<TS::ThreadHandle>), TS::SpawnError>
+    where
+        TS: TryThreadSpawner<()>,
+    {
+        let queue = Arc::new(message_queue);
+        let mut handles = Vec::with_capacity(workers);
+        for _ in 0..workers {
+            let queue_clone = queue.clone();
+            match TS::try_spawn(move || Self::worker_function(&*queue_clone)) {
+                Ok(handle) => handles.push(handle),
+                Err(error) => {
+                    for _ in 0..handles.len() {
+                        queue.push(BridgeMessage::Stop);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok((
+            Self {
+                inner: Arc::new(BlockingBridgeInner { queue, workers }),
+            },
+            handles,
+        ))
+    }
+
+    fn worker_function(queue: &Q) {
+        loop {
+            match queue.pop() {
+                BridgeMessage::Job(job) => job(),
+                BridgeMessage::Stop => return,
+            }
+        }
+    }
+}
+impl<Q> BlockingBridge<Q>
+where
+    Q: Queue<Item = BridgeMessage>,
+{
+    /// Sends a job to be run on one of the workers.
+    pub fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.inner
+            .queue
+            .try_push(BridgeMessage::Job(Box::new(job)))
+            .unwrap_or_else(|_| panic!("Could not add to message queue"));
+    }
+
+    /// The number of workers this bridge was created with.
+    pub fn workers(&self) -> usize {
+        self.inner.workers
+    }
+}
+impl<Q> Clone for BlockingBridge<Q>
+where
+    Q: Queue<Item = BridgeMessage>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BlockingBridgeInner<Q>
+where
+    Q: Queue<Item = BridgeMessage>,
+{
+    queue: Arc<Q>,
+    workers: usize,
+}
+impl<Q> Drop for BlockingBridgeInner<Q>
+where
+    Q: Queue<Item = BridgeMessage>,
+{
+    fn drop(&mut self) {
+        for _ in 0..self.workers {
+            self.queue.push(BridgeMessage::Stop);
+        }
+    }
+}
+
+/// Hands messages of type `M` to the thread or threads servicing an async converter such as
+/// [`RawCustomAsyncMutex`](crate::mutex::RawCustomAsyncMutex).
+///
+/// Implemented for any [`Queue`] of `M`, which is popped by a dedicated thread, and for
+/// [`BlockingBridge`], which runs `handler` on one of its workers.
+pub trait MessageSender<M> {
+    /// Sends a message to be handled by `handler`.
+    fn send(&self, message: M, handler: impl FnOnce(M) + Send + 'static);
+}
+impl<Q, M> MessageSender<M> for Q
+where
+    Q: Queue<Item = M>,
+{
+    fn send(&self, message: M, _handler: impl FnOnce(M) + Send + 'static) {
+        self.try_push(message)
+            .unwrap_or_else(|_| panic!("Could not add to message queue"));
+    }
+}
+impl<Q, M> MessageSender<M> for BlockingBridge<Q>
+where
+    Q: Queue<Item = BridgeMessage>,
+    M: Send + 'static,
+{
+    fn send(&self, message: M, handler: impl FnOnce(M) + Send + 'static) {
+        self.execute(move || handler(message))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::blocking_bridge::BlockingBridge;
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn shutdown_test() {
+        let (bridge, handles) =
+            BlockingBridge::new::<StdThreadFunctions>(4, ParkQueueStd::default())
+                .expect("Could not spawn");
+        let count = Arc::new(AtomicUsize::new(0));
+        let bridge_clone = bridge.clone();
+        for _ in 0..100 {
+            let count = count.clone();
+            bridge_clone.execute(move || {
+                count.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        drop(bridge);
+        drop(bridge_clone);
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 100);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::condvar::TryCondvar;
+use crate::mutex::{CustomMutexGuard, RawAsyncMutex};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use core::ops::DerefMut;
+
+/// A generic condition variable that can be waited on asynchronously.
+#[async_trait]
+pub trait AsyncCondvar: TryCondvar {
+    /// Unlocks the mutex of `guard` and waits until notified, then relocks it
+    /// asynchronously. May wake spuriously.
+    async fn wait_async<'a, T, M>(
+        &self,
+        guard: CustomMutexGuard<'a, T, M>,
+    ) -> CustomMutexGuard<'a, T, M>
+    where
+        T: Send + 'a,
+        M: RawAsyncMutex + Send + Sync + 'a;
+
+    /// Waits asynchronously while `condition` returns [`true`] for the locked
+    /// data.
+    async fn wait_while_async<'a, T, M, F>(
+        &self,
+        mut guard: CustomMutexGuard<'a, T, M>,
+        mut condition: F,
+    ) -> CustomMutexGuard<'a, T, M>
+    where
+        T: Send + 'a,
+        M: RawAsyncMutex + Send + Sync + 'a,
+        F: for<'b> FnMut(&'b mut T) -> bool + Send + 'a,
+    {
+        while condition(guard.deref_mut()) {
+            guard = self.wait_async(guard).await;
+        }
+        guard
+    }
+}
@@ -0,0 +1,134 @@
+use crate::condvar::{AsyncCondvar, TryCondvar};
+use crate::mutex::{AsyncMutex, CustomMutexGuard, Mutex, RawAsyncMutex, SpinLock};
+use crate::ThreadFunctions;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use async_trait::async_trait;
+use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
+
+/// A condition variable that can only be waited on through async await.
+#[derive(Debug)]
+pub struct FullAsyncCondvar<CS> {
+    waiters: SpinLock<VecDeque<CompleteFutureHandle>, CS>,
+}
+impl<CS> Default for FullAsyncCondvar<CS> {
+    fn default() -> Self {
+        Self {
+            waiters: SpinLock::new(VecDeque::new()),
+        }
+    }
+}
+impl<CS> TryCondvar for FullAsyncCondvar<CS>
+where
+    CS: ThreadFunctions,
+{
+    fn notify_one(&self) {
+        let mut guard = self.waiters.lock();
+        while let Some(handle) = guard.pop_front() {
+            // `None` if the waiting future was dropped
+            if let Some(result) = handle.complete() {
+                debug_assert!(!result);
+                return;
+            }
+        }
+    }
+
+    fn notify_all(&self) {
+        let waiters = core::mem::take(&mut *self.waiters.lock());
+        for handle in waiters {
+            handle.complete();
+        }
+    }
+}
+#[async_trait]
+impl<CS> AsyncCondvar for FullAsyncCondvar<CS>
+where
+    CS: ThreadFunctions + Send + Sync,
+{
+    async fn wait_async<'a, T, M>(
+        &self,
+        guard: CustomMutexGuard<'a, T, M>,
+    ) -> CustomMutexGuard<'a, T, M>
+    where
+        T: Send + 'a,
+        M: RawAsyncMutex + Send + Sync + 'a,
+    {
+        let future = CompleteFuture::new();
+        self.waiters.lock().push_back(future.get_handle());
+        let mutex = guard.mutex;
+        drop(guard);
+        future.await;
+        mutex.lock_async().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::condvar::{AsyncCondvar, FullAsyncCondvar, TryCondvar};
+    #[cfg(feature = "std")]
+    use crate::mutex::{AsyncMutex, FullAsyncMutex};
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::future::Future;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::task::{Context, Poll, Wake};
+    #[cfg(feature = "std")]
+    use std::thread::{self, Thread};
+
+    #[cfg(feature = "std")]
+    struct ThreadWaker(Thread);
+    #[cfg(feature = "std")]
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        loop {
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(out) => return out,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn full_async_condvar_test() {
+        let pair = Arc::new((
+            FullAsyncMutex::<_, ParkQueueStd<_>>::new(false),
+            FullAsyncCondvar::<StdThreadFunctions>::default(),
+        ));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let pair = pair.clone();
+                thread::spawn(move || {
+                    block_on(async {
+                        let (mutex, condvar) = &*pair;
+                        let guard = condvar
+                            .wait_while_async(mutex.lock_async().await, |ready| !*ready)
+                            .await;
+                        assert!(*guard);
+                    })
+                })
+            })
+            .collect();
+        let (mutex, condvar) = &*pair;
+        thread::sleep(std::time::Duration::from_millis(10));
+        *block_on(mutex.lock_async()) = true;
+        condvar.notify_all();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+    }
+}
@@ -0,0 +1,8 @@
+mod async_condvar;
+pub use async_condvar::*;
+
+mod monitor;
+pub use monitor::*;
+
+mod park_condvar;
+pub use park_condvar::*;
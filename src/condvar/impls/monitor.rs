@@ -0,0 +1,232 @@
+use crate::condvar::{
+    AsyncCondvar, Condvar, FullAsyncCondvar, ParkCondvar, TimeoutCondvar, TryCondvar,
+};
+use crate::mutex::{
+    AsyncMutex, CustomMutex, CustomMutexGuard, Mutex, RawAsyncMutex, RawMutex, RawTryMutex,
+};
+use crate::{ThreadFunctions, ThreadParker, ThreadTimeoutParker, TimeFunctions};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::time::Duration;
+
+/// A [`Monitor`] that uses std functions and a
+/// [`RawParkMutex`](crate::mutex::RawParkMutex).
+#[cfg(feature = "std")]
+pub type MonitorStd<T> = Monitor<T, crate::mutex::RawParkMutexStd, crate::StdThreadFunctions>;
+
+/// A [`CustomMutex`] paired with condition variables. State is changed with
+/// [`Monitor::mutate_and_notify`] or through a [`MonitorGuard`], both of which
+/// wake everything waiting for a predicate on the state with
+/// [`Monitor::wait_until`] or its variants.
+#[derive(Debug)]
+pub struct Monitor<T, M, CS>
+where
+    CS: ThreadParker,
+{
+    mutex: CustomMutex<T, M>,
+    condvar: ParkCondvar<CS>,
+    async_condvar: FullAsyncCondvar<CS>,
+}
+impl<T, M, CS> Monitor<T, M, CS>
+where
+    CS: ThreadParker,
+{
+    /// Creates a new [`Monitor`] with a raw mutex
+    pub fn from_raw(raw_mutex: M, data: T) -> Self {
+        Self {
+            mutex: CustomMutex::from_raw(raw_mutex, data),
+            condvar: ParkCondvar::default(),
+            async_condvar: FullAsyncCondvar::default(),
+        }
+    }
+
+    /// Creates a new [`Monitor`] with a default raw mutex
+    pub fn new(data: T) -> Self
+    where
+        M: Default,
+    {
+        Self::from_raw(M::default(), data)
+    }
+}
+impl<T, M, CS> Monitor<T, M, CS>
+where
+    M: RawMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    /// Blocks until `condition` returns [`true`] for the state, returning the
+    /// locked state.
+    pub fn wait_until(&self, mut condition: impl FnMut(&T) -> bool) -> MonitorGuard<'_, T, M, CS> {
+        let mut guard = self.mutex.lock();
+        while !condition(&guard) {
+            guard = self.condvar.wait(guard);
+        }
+        MonitorGuard::new(self, guard)
+    }
+
+    /// Locks the state and runs `func` on it, then wakes everything waiting on
+    /// this monitor.
+    pub fn mutate_and_notify<O>(&self, func: impl FnOnce(&mut T) -> O) -> O {
+        let out = func(&mut self.mutex.lock());
+        self.notify();
+        out
+    }
+}
+impl<T, M, CS> Monitor<T, M, CS>
+where
+    M: RawMutex,
+    CS: ThreadTimeoutParker + ThreadFunctions + TimeFunctions,
+{
+    /// Blocks until `condition` returns [`true`] for the state or times out.
+    /// Returns the locked state or [`None`] if timed out.
+    pub fn wait_until_timeout(
+        &self,
+        mut condition: impl FnMut(&T) -> bool,
+        timeout: Duration,
+    ) -> Option<MonitorGuard<'_, T, M, CS>> {
+        let end = CS::current_time() + timeout;
+        let mut guard = self.mutex.lock();
+        while !condition(&guard) {
+            let current_time = CS::current_time();
+            if current_time >= end {
+                return None;
+            }
+            guard = self.condvar.wait_timeout(guard, end - current_time).0;
+        }
+        Some(MonitorGuard::new(self, guard))
+    }
+}
+impl<T, M, CS> Monitor<T, M, CS>
+where
+    T: Send,
+    M: RawAsyncMutex + Send + Sync,
+    CS: ThreadParker + ThreadFunctions + Send + Sync,
+{
+    /// Waits asynchronously until `condition` returns [`true`] for the state,
+    /// returning the locked state.
+    pub async fn wait_until_async(
+        &self,
+        mut condition: impl FnMut(&T) -> bool,
+    ) -> MonitorGuard<'_, T, M, CS> {
+        let mut guard = self.mutex.lock_async().await;
+        while !condition(&guard) {
+            guard = self.async_condvar.wait_async(guard).await;
+        }
+        MonitorGuard::new(self, guard)
+    }
+
+    /// Locks the state asynchronously and runs `func` on it, then wakes
+    /// everything waiting on this monitor.
+    pub async fn mutate_and_notify_async<O>(&self, func: impl FnOnce(&mut T) -> O) -> O {
+        let out = func(&mut *self.mutex.lock_async().await);
+        self.notify();
+        out
+    }
+}
+impl<T, M, CS> Monitor<T, M, CS>
+where
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn notify(&self) {
+        self.condvar.notify_all();
+        self.async_condvar.notify_all();
+    }
+}
+impl<T, M, CS> Default for Monitor<T, M, CS>
+where
+    T: Default,
+    M: Default,
+    CS: ThreadParker,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// The locked state of a [`Monitor`] returned by its waits. Wakes everything
+/// waiting on the monitor when dropped as the state may have been changed
+/// through it.
+#[derive(Debug)]
+pub struct MonitorGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    monitor: &'a Monitor<T, M, CS>,
+    guard: ManuallyDrop<CustomMutexGuard<'a, T, M>>,
+}
+impl<'a, T, M, CS> MonitorGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn new(monitor: &'a Monitor<T, M, CS>, guard: CustomMutexGuard<'a, T, M>) -> Self {
+        Self {
+            monitor,
+            guard: ManuallyDrop::new(guard),
+        }
+    }
+}
+impl<'a, T, M, CS> Deref for MonitorGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+impl<'a, T, M, CS> DerefMut for MonitorGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+impl<'a, T, M, CS> Drop for MonitorGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.guard) }
+        self.monitor.notify();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::condvar::MonitorStd;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn monitor_test() {
+        let monitor = Arc::new(MonitorStd::new(0usize));
+        assert!(monitor
+            .wait_until_timeout(|count| *count > 0, Duration::from_millis(10))
+            .is_none());
+        let handles: Vec<_> = (1..=4)
+            .map(|turn| {
+                let monitor = monitor.clone();
+                spawn(move || {
+                    *monitor.wait_until(|count| *count == turn) += 1;
+                })
+            })
+            .collect();
+        monitor.mutate_and_notify(|count| *count = 1);
+        assert_eq!(*monitor.wait_until(|count| *count == 5), 5);
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+    }
+}
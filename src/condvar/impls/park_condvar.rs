@@ -0,0 +1,159 @@
+use crate::condvar::{Condvar, TimeoutCondvar, TryCondvar};
+use crate::mutex::{CustomMutexGuard, Mutex, RawMutex, SpinLock};
+use crate::{ThreadFunctions, ThreadParker, ThreadTimeoutParker, TimeFunctions};
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+/// A [`ParkCondvar`] that uses std functions.
+#[cfg(feature = "std")]
+pub type ParkCondvarStd = ParkCondvar<crate::StdThreadFunctions>;
+
+type Parkers<CS> = VecDeque<(<CS as ThreadParker>::ThreadId, Arc<AtomicBool>)>;
+
+/// A condition variable based on thread parking.
+#[derive(Debug)]
+pub struct ParkCondvar<CS>
+where
+    CS: ThreadParker,
+{
+    parkers: SpinLock<Parkers<CS>, CS>,
+}
+impl<CS> ParkCondvar<CS>
+where
+    CS: ThreadParker + ThreadFunctions,
+{
+    /// Adds the current thread to the waiting list, returning the flag set when
+    /// notified.
+    fn add_parker(&self) -> Arc<AtomicBool> {
+        let should_wake = Arc::new(AtomicBool::new(false));
+        self.parkers
+            .lock()
+            .push_back((CS::current_thread(), should_wake.clone()));
+        should_wake
+    }
+}
+impl<CS> Default for ParkCondvar<CS>
+where
+    CS: ThreadParker,
+{
+    fn default() -> Self {
+        Self {
+            parkers: SpinLock::new(VecDeque::new()),
+        }
+    }
+}
+impl<CS> TryCondvar for ParkCondvar<CS>
+where
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn notify_one(&self) {
+        if let Some((thread_id, should_wake)) = self.parkers.lock().pop_front() {
+            should_wake.store(true, Ordering::Release);
+            CS::unpark(thread_id);
+        }
+    }
+
+    fn notify_all(&self) {
+        let parkers = core::mem::take(&mut *self.parkers.lock());
+        for (thread_id, should_wake) in parkers {
+            should_wake.store(true, Ordering::Release);
+            CS::unpark(thread_id);
+        }
+    }
+}
+impl<CS> Condvar for ParkCondvar<CS>
+where
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn wait<'a, T, M>(&self, guard: CustomMutexGuard<'a, T, M>) -> CustomMutexGuard<'a, T, M>
+    where
+        M: RawMutex,
+    {
+        // Added before unlocking so a notify after unlocking is not missed
+        let should_wake = self.add_parker();
+        let mutex = guard.mutex;
+        drop(guard);
+        while !should_wake.load(Ordering::Acquire) {
+            CS::park();
+        }
+        mutex.lock()
+    }
+}
+impl<CS> TimeoutCondvar for ParkCondvar<CS>
+where
+    CS: ThreadTimeoutParker + ThreadFunctions + TimeFunctions,
+{
+    fn wait_timeout<'a, T, M>(
+        &self,
+        guard: CustomMutexGuard<'a, T, M>,
+        timeout: Duration,
+    ) -> (CustomMutexGuard<'a, T, M>, bool)
+    where
+        M: RawMutex,
+    {
+        let should_wake = self.add_parker();
+        let mutex = guard.mutex;
+        drop(guard);
+        let end = CS::current_time() + timeout;
+        let notified = loop {
+            if should_wake.load(Ordering::Acquire) {
+                break true;
+            }
+            let current_time = CS::current_time();
+            if current_time >= end {
+                let mut parkers = self.parkers.lock();
+                // May have been notified before the lock was taken
+                if should_wake.load(Ordering::Acquire) {
+                    break true;
+                }
+                parkers.retain(|(_, parker)| !Arc::ptr_eq(parker, &should_wake));
+                break false;
+            }
+            CS::park_timeout(end - current_time);
+        };
+        (mutex.lock(), notified)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::condvar::{Condvar, ParkCondvarStd, TimeoutCondvar, TryCondvar};
+    #[cfg(feature = "std")]
+    use crate::mutex::{Mutex, ParkMutexStd};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn park_condvar_test() {
+        const THREADS: usize = 4;
+        let pair = Arc::new((ParkMutexStd::new(0usize), ParkCondvarStd::default()));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let pair = pair.clone();
+                spawn(move || {
+                    let (mutex, condvar) = &*pair;
+                    let mut guard = condvar.wait_while(mutex.lock(), |started| *started == 0);
+                    *guard += 1;
+                })
+            })
+            .collect();
+        let (mutex, condvar) = &*pair;
+        let (guard, notified) = condvar.wait_timeout(mutex.lock(), Duration::from_millis(10));
+        assert!(!notified);
+        drop(guard);
+        *mutex.lock() = 1;
+        condvar.notify_all();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(*mutex.lock(), THREADS + 1);
+    }
+}
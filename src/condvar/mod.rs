@@ -0,0 +1,45 @@
+//! Traits and implementations for condition variables.
+
+#[cfg(feature = "alloc")]
+mod impls;
+#[cfg(feature = "alloc")]
+pub use impls::*;
+
+#[cfg(feature = "alloc")]
+mod r#async;
+#[cfg(feature = "alloc")]
+pub use r#async::*;
+
+mod timeout;
+pub use timeout::*;
+
+mod r#try;
+pub use r#try::*;
+
+use crate::mutex::{CustomMutexGuard, RawMutex};
+use core::ops::DerefMut;
+
+/// A generic condition variable that works with guards of
+/// [`CustomMutex`](crate::mutex::CustomMutex).
+pub trait Condvar: TryCondvar {
+    /// Unlocks the mutex of `guard` and blocks until notified, then relocks
+    /// it. May wake spuriously.
+    fn wait<'a, T, M>(&self, guard: CustomMutexGuard<'a, T, M>) -> CustomMutexGuard<'a, T, M>
+    where
+        M: RawMutex;
+
+    /// Waits while `condition` returns [`true`] for the locked data.
+    fn wait_while<'a, T, M>(
+        &self,
+        mut guard: CustomMutexGuard<'a, T, M>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> CustomMutexGuard<'a, T, M>
+    where
+        M: RawMutex,
+    {
+        while condition(guard.deref_mut()) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+}
@@ -0,0 +1,17 @@
+use crate::condvar::Condvar;
+use crate::mutex::{CustomMutexGuard, RawMutex};
+use core::time::Duration;
+
+/// A generic condition variable that can timeout.
+pub trait TimeoutCondvar: Condvar {
+    /// Unlocks the mutex of `guard` and blocks until notified or timed out,
+    /// then relocks it. Returns [`true`] if notified or [`false`] if timed
+    /// out.
+    fn wait_timeout<'a, T, M>(
+        &self,
+        guard: CustomMutexGuard<'a, T, M>,
+        timeout: Duration,
+    ) -> (CustomMutexGuard<'a, T, M>, bool)
+    where
+        M: RawMutex;
+}
@@ -0,0 +1,7 @@
+/// A generic condition variable that has non-blocking operations.
+pub trait TryCondvar {
+    /// Wakes up one waiting thread or task if there is one.
+    fn notify_one(&self);
+    /// Wakes up all waiting threads and tasks.
+    fn notify_all(&self);
+}
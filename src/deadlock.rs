@@ -0,0 +1,303 @@
+//! Deadlock detection for [`RawParkMutex`](crate::mutex::RawParkMutex) and
+//! [`RawSpinRwLock`](crate::rw_lock::RawSpinRwLock), enabled with the
+//! `deadlock_detection` feature.
+//!
+//! Owners of each lock and the lock each thread is blocked on are recorded in
+//! a global wait-for graph. Every blocking acquisition checks the graph for a
+//! cycle through the blocking thread and records any found for
+//! [`check_deadlocks`].
+//!
+//! Threads are identified with [`std::thread::current`] rather than the
+//! lock's `CS::ThreadId`, so only locks used from std threads are tracked
+//! correctly. Guards of tracked locks shared by several holders must be
+//! dropped on the thread that locked them.
+
+use crate::StdThread;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::vec::Vec;
+
+static GRAPH: Mutex<WaitGraph> = Mutex::new(WaitGraph {
+    locks: Vec::new(),
+    waiting: Vec::new(),
+    deadlocks: Vec::new(),
+});
+
+/// Returns the deadlocks found since the last call.
+pub fn check_deadlocks() -> Vec<Deadlock> {
+    core::mem::take(&mut graph().deadlocks)
+}
+
+/// A cycle of threads that are each blocked on a lock held by the next.
+pub type Deadlock = Vec<DeadlockedThread>;
+
+/// A thread that is part of a [`Deadlock`].
+#[derive(Clone, Debug)]
+pub struct DeadlockedThread {
+    /// The thread
+    pub thread: StdThread,
+    /// The lock the thread is blocked on
+    pub waiting_for: LockIdentity,
+    /// The locks the thread holds
+    pub holding: Vec<LockIdentity>,
+}
+
+/// Identifies a lock in a [`Deadlock`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LockIdentity {
+    /// The address of the raw lock
+    pub address: usize,
+    /// The type name of the raw lock
+    pub type_name: &'static str,
+}
+impl LockIdentity {
+    fn of<L>(lock: &L) -> Self {
+        Self {
+            address: lock as *const L as usize,
+            type_name: core::any::type_name::<L>(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct WaitGraph {
+    /// Locks with at least one holder
+    locks: Vec<(LockIdentity, Vec<StdThread>)>,
+    /// The lock each blocked thread is waiting for and whether it is
+    /// upgrading a lock it already holds
+    waiting: Vec<(StdThread, LockIdentity, bool)>,
+    deadlocks: Vec<Deadlock>,
+}
+impl WaitGraph {
+    fn holders(&self, lock: LockIdentity) -> &[StdThread] {
+        self.locks
+            .iter()
+            .find(|(identity, _)| *identity == lock)
+            .map_or(&[], |(_, holders)| holders)
+    }
+
+    fn waiting_for(&self, thread: &StdThread) -> Option<(LockIdentity, bool)> {
+        self.waiting
+            .iter()
+            .find(|(waiting, _, _)| waiting == thread)
+            .map(|(_, lock, upgrading)| (*lock, *upgrading))
+    }
+
+    fn stop_waiting(&mut self, thread: &StdThread) {
+        self.waiting.retain(|(waiting, _, _)| waiting != thread);
+    }
+
+    /// Finds a path of threads from `from` back to `start`, each waiting on a
+    /// lock held by the next.
+    fn find_cycle(
+        &self,
+        start: &StdThread,
+        from: &StdThread,
+        visited: &mut Vec<StdThread>,
+    ) -> Option<Vec<StdThread>> {
+        let (lock, upgrading) = self.waiting_for(from)?;
+        for holder in self.holders(lock) {
+            // An upgrading thread only waits for the other holders
+            if upgrading && holder == from {
+                continue;
+            }
+            if holder == start {
+                return Some(vec![from.clone()]);
+            }
+            if visited.contains(holder) {
+                continue;
+            }
+            visited.push(holder.clone());
+            if let Some(mut path) = self.find_cycle(start, holder, visited) {
+                path.insert(0, from.clone());
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    fn report(&self, thread: StdThread) -> DeadlockedThread {
+        DeadlockedThread {
+            waiting_for: self.waiting_for(&thread).expect("Thread is not waiting").0,
+            holding: self
+                .locks
+                .iter()
+                .filter(|(_, holders)| holders.contains(&thread))
+                .map(|(lock, _)| *lock)
+                .collect(),
+            thread,
+        }
+    }
+}
+
+fn graph() -> MutexGuard<'static, WaitGraph> {
+    GRAPH.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn current() -> StdThread {
+    StdThread(std::thread::current())
+}
+
+/// Records that the current thread acquired `lock`.
+pub(crate) fn acquired<L>(lock: &L) {
+    let lock = LockIdentity::of(lock);
+    let thread = current();
+    let mut graph = graph();
+    graph.stop_waiting(&thread);
+    match graph
+        .locks
+        .iter_mut()
+        .find(|(identity, _)| *identity == lock)
+    {
+        None => graph.locks.push((lock, vec![thread])),
+        Some((_, holders)) => holders.push(thread),
+    }
+}
+
+/// Records that the current thread released `lock`. A lock with a single
+/// holder may be released by any thread, such as by the async converters that
+/// lock on a worker thread.
+pub(crate) fn released<L>(lock: &L) {
+    let lock = LockIdentity::of(lock);
+    let thread = current();
+    let mut graph = graph();
+    let index = graph
+        .locks
+        .iter()
+        .position(|(identity, _)| *identity == lock);
+    debug_assert!(index.is_some(), "Released a lock with no holders");
+    if let Some(index) = index {
+        let holders = &mut graph.locks[index].1;
+        let holder = match holders.iter().position(|holder| *holder == thread) {
+            Some(holder) => Some(holder),
+            None if holders.len() == 1 => Some(0),
+            None => None,
+        };
+        debug_assert!(
+            holder.is_some(),
+            "Lock released by a thread that does not hold it"
+        );
+        if let Some(holder) = holder {
+            holders.remove(holder);
+            if holders.is_empty() {
+                graph.locks.swap_remove(index);
+            }
+        }
+    }
+}
+
+/// Records that the current thread is blocking on `lock` and checks for a
+/// deadlock.
+pub(crate) fn waiting<L>(lock: &L) {
+    wait(LockIdentity::of(lock), false)
+}
+
+/// Records that the current thread is blocking to upgrade its hold on `lock`
+/// and checks for a deadlock. Call [`stopped_waiting`] once done.
+pub(crate) fn waiting_upgrade<L>(lock: &L) {
+    wait(LockIdentity::of(lock), true)
+}
+
+fn wait(lock: LockIdentity, upgrading: bool) {
+    let thread = current();
+    let mut graph = graph();
+    graph.stop_waiting(&thread);
+    graph.waiting.push((thread.clone(), lock, upgrading));
+    if let Some(cycle) = graph.find_cycle(&thread, &thread, &mut Vec::new()) {
+        let deadlock = cycle
+            .into_iter()
+            .map(|thread| graph.report(thread))
+            .collect();
+        graph.deadlocks.push(deadlock);
+    }
+}
+
+/// Records that the current thread stopped blocking without acquiring, or
+/// finished upgrading.
+pub(crate) fn stopped_waiting() {
+    graph().stop_waiting(&current());
+}
+
+#[cfg(test)]
+mod test {
+    use crate::deadlock::{check_deadlocks, LockIdentity};
+    use crate::mutex::{Mutex, ParkMutexStd, TimeoutMutex};
+    use crate::rw_lock::{RwLock, SpinRwLock, UpgradeReadGuard, UpgradeTimeoutReadGuard};
+    use crate::StdThreadFunctions;
+    use std::sync::Barrier;
+    use std::thread::scope;
+    use std::time::Duration;
+
+    #[test]
+    fn deadlock_detection_test() {
+        let mutexes = (ParkMutexStd::new(()), ParkMutexStd::new(()));
+        let identities = [
+            LockIdentity::of(&mutexes.0.raw_mutex),
+            LockIdentity::of(&mutexes.1.raw_mutex),
+        ];
+        let barrier = Barrier::new(2);
+        let deadlocks = scope(|scope| {
+            scope.spawn(|| {
+                let _guard = mutexes.0.lock();
+                barrier.wait();
+                // Acquired once the other thread gives up
+                let _guard = mutexes.1.lock();
+            });
+            let guard = mutexes.1.lock();
+            barrier.wait();
+            // Waits until the other thread is blocked, detecting the deadlock
+            let mut deadlocks = Vec::new();
+            for _ in 0..500 {
+                assert!(mutexes.0.lock_timeout(Duration::from_millis(10)).is_none());
+                deadlocks.extend(check_deadlocks().into_iter().filter(|deadlock| {
+                    deadlock
+                        .iter()
+                        .all(|thread| identities.contains(&thread.waiting_for))
+                }));
+                if !deadlocks.is_empty() {
+                    break;
+                }
+            }
+            drop(guard);
+            deadlocks
+        });
+        assert_eq!(deadlocks.len(), 1);
+        assert_eq!(deadlocks[0].len(), 2);
+        for thread in &deadlocks[0] {
+            assert_eq!(thread.holding.len(), 1);
+            assert_ne!(thread.holding[0], thread.waiting_for);
+        }
+    }
+
+    #[test]
+    fn upgrade_deadlock_test() {
+        let lock = SpinRwLock::<_, StdThreadFunctions>::new(());
+        let identity = LockIdentity::of(&lock.raw_lock);
+        let barrier = Barrier::new(2);
+        let deadlocks = scope(|scope| {
+            scope.spawn(|| {
+                let guard = lock.read();
+                barrier.wait();
+                // Upgrades once the other thread gives up its read
+                drop(guard.upgrade());
+            });
+            let mut guard = lock.read();
+            barrier.wait();
+            let mut deadlocks = Vec::new();
+            for _ in 0..500 {
+                guard = guard
+                    .upgrade_timeout(Duration::from_millis(10))
+                    .expect_err("Should not upgrade");
+                deadlocks.extend(check_deadlocks().into_iter().filter(|deadlock| {
+                    deadlock.iter().all(|thread| thread.waiting_for == identity)
+                }));
+                if !deadlocks.is_empty() {
+                    break;
+                }
+            }
+            drop(guard);
+            deadlocks
+        });
+        assert_eq!(deadlocks.len(), 1);
+        assert_eq!(deadlocks[0].len(), 2);
+    }
+}
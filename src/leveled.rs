@@ -0,0 +1,410 @@
+//! Lock levels for catching lock order violations. A thread may only acquire
+//! a leveled lock with a higher level than every leveled lock it holds.
+//!
+//! Levels are only tracked in debug builds with the `std` feature, otherwise
+//! [`Leveled`] only forwards to the wrapped lock. Guards must be released on
+//! the thread that acquired them, which is asserted in debug builds.
+
+use crate::mutex::{
+    CustomMutex, CustomMutexGuard, FallibleMutex, RawMutex, RawTimeoutMutex, RawTryMutex,
+    TimeoutFallibleMutex, TryFallibleMutex,
+};
+use crate::rw_lock::{
+    CustomReadGuard, CustomRwLock, CustomWriteGuard, FallibleRwLock, RawDowngradeRwLock, RawRwLock,
+    RawTimeoutRwLock, RawTryRwLock, RawTryUpgradeRwLock, RawUpgradeRwLock, TimeoutFallibleRwLock,
+    TryFallibleRwLock,
+};
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+
+#[cfg(all(debug_assertions, feature = "std"))]
+std::thread_local! {
+    static HELD_LEVELS: core::cell::RefCell<std::vec::Vec<u32>> = Default::default();
+}
+
+/// A [`CustomMutex`] at lock level `LEVEL`.
+pub type LeveledMutex<T, M, const LEVEL: u32> = CustomMutex<T, Leveled<M, LEVEL>>;
+
+/// A [`CustomRwLock`] at lock level `LEVEL`.
+pub type LeveledRwLock<T, R, const LEVEL: u32> = CustomRwLock<T, Leveled<R, LEVEL>>;
+
+/// The error when acquiring a lock with a level not higher than one already
+/// held by the thread.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LevelViolation {
+    /// The level of the lock being acquired
+    pub level: u32,
+    /// The highest level held by the thread
+    pub held: u32,
+}
+impl Display for LevelViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Lock level {} acquired while holding level {}",
+            self.level, self.held
+        )
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for LevelViolation {}
+
+/// Wraps a raw mutex or rw lock, giving it lock level `LEVEL`. Panics when
+/// locked out of order, or returns a [`LevelViolation`] through the fallible
+/// lock traits.
+#[derive(Debug, Default)]
+pub struct Leveled<R, const LEVEL: u32> {
+    raw: R,
+}
+impl<R, const LEVEL: u32> Leveled<R, LEVEL> {
+    /// Wraps a raw lock
+    pub const fn new(raw: R) -> Self {
+        Self { raw }
+    }
+
+    /// Checks that the current thread can acquire this level.
+    #[inline]
+    fn check(&self) -> Result<(), LevelViolation> {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        {
+            let held = HELD_LEVELS.with(|levels| levels.borrow().iter().copied().max());
+            if let Some(held) = held {
+                if held >= LEVEL {
+                    return Err(LevelViolation { level: LEVEL, held });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn check_or_panic(&self) {
+        if let Err(error) = self.check() {
+            panic!("{}", error)
+        }
+    }
+
+    #[inline]
+    fn acquired(&self, acquired: bool) -> bool {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        if acquired {
+            HELD_LEVELS.with(|levels| levels.borrow_mut().push(LEVEL));
+        }
+        acquired
+    }
+
+    #[inline]
+    fn released(&self) {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        HELD_LEVELS.with(|levels| {
+            let mut levels = levels.borrow_mut();
+            let index = levels.iter().rposition(|level| *level == LEVEL);
+            debug_assert!(
+                index.is_some(),
+                "Lock level {} released on a thread that does not hold it",
+                LEVEL
+            );
+            if let Some(index) = index {
+                levels.remove(index);
+            }
+        });
+    }
+}
+unsafe impl<M, const LEVEL: u32> RawTryMutex for Leveled<M, LEVEL>
+where
+    M: RawTryMutex,
+{
+    #[inline]
+    fn try_lock(&self) -> bool {
+        self.check_or_panic();
+        self.acquired(self.raw.try_lock())
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        self.released();
+        self.raw.unlock()
+    }
+}
+unsafe impl<M, const LEVEL: u32> RawMutex for Leveled<M, LEVEL>
+where
+    M: RawMutex,
+{
+    #[inline]
+    fn lock(&self) {
+        self.check_or_panic();
+        self.raw.lock();
+        self.acquired(true);
+    }
+}
+unsafe impl<M, const LEVEL: u32> RawTimeoutMutex for Leveled<M, LEVEL>
+where
+    M: RawTimeoutMutex,
+{
+    #[inline]
+    fn lock_timeout(&self, timeout: Duration) -> bool {
+        self.check_or_panic();
+        self.acquired(self.raw.lock_timeout(timeout))
+    }
+}
+unsafe impl<R, const LEVEL: u32> RawTryRwLock for Leveled<R, LEVEL>
+where
+    R: RawTryRwLock,
+{
+    #[inline]
+    fn try_add_reader(&self) -> bool {
+        self.check_or_panic();
+        self.acquired(self.raw.try_add_reader())
+    }
+
+    #[inline]
+    fn try_add_writer(&self) -> bool {
+        self.check_or_panic();
+        self.acquired(self.raw.try_add_writer())
+    }
+
+    #[inline]
+    unsafe fn remove_reader(&self) {
+        self.released();
+        self.raw.remove_reader()
+    }
+
+    #[inline]
+    unsafe fn remove_writer(&self) {
+        self.released();
+        self.raw.remove_writer()
+    }
+}
+unsafe impl<R, const LEVEL: u32> RawRwLock for Leveled<R, LEVEL>
+where
+    R: RawRwLock,
+{
+    #[inline]
+    fn add_reader(&self) {
+        self.check_or_panic();
+        self.raw.add_reader();
+        self.acquired(true);
+    }
+
+    #[inline]
+    fn add_writer(&self) {
+        self.check_or_panic();
+        self.raw.add_writer();
+        self.acquired(true);
+    }
+}
+unsafe impl<R, const LEVEL: u32> RawTimeoutRwLock for Leveled<R, LEVEL>
+where
+    R: RawTimeoutRwLock,
+{
+    #[inline]
+    fn add_reader_timeout(&self, timeout: Duration) -> bool {
+        self.check_or_panic();
+        self.acquired(self.raw.add_reader_timeout(timeout))
+    }
+
+    #[inline]
+    fn add_writer_timeout(&self, timeout: Duration) -> bool {
+        self.check_or_panic();
+        self.acquired(self.raw.add_writer_timeout(timeout))
+    }
+}
+unsafe impl<R, const LEVEL: u32> RawTryUpgradeRwLock for Leveled<R, LEVEL>
+where
+    R: RawTryUpgradeRwLock,
+{
+    #[inline]
+    unsafe fn try_upgrade(&self) -> bool {
+        self.raw.try_upgrade()
+    }
+}
+unsafe impl<R, const LEVEL: u32> RawUpgradeRwLock for Leveled<R, LEVEL>
+where
+    R: RawUpgradeRwLock,
+{
+    #[inline]
+    unsafe fn upgrade(&self) {
+        self.raw.upgrade()
+    }
+}
+unsafe impl<R, const LEVEL: u32> RawDowngradeRwLock for Leveled<R, LEVEL>
+where
+    R: RawDowngradeRwLock,
+{
+    #[inline]
+    unsafe fn downgrade(&self) {
+        self.raw.downgrade()
+    }
+}
+
+impl<'a, T, M, const LEVEL: u32> TryFallibleMutex<'a> for CustomMutex<T, Leveled<M, LEVEL>>
+where
+    T: 'a,
+    M: RawTryMutex + 'a,
+{
+    type Item = T;
+    type Guard = CustomMutexGuard<'a, T, Leveled<M, LEVEL>>;
+    type Error = LevelViolation;
+
+    fn try_lock_fallible(&'a self) -> Result<Option<Self::Guard>, Self::Error> {
+        self.raw_mutex.check()?;
+        let locked = self.raw_mutex.acquired(self.raw_mutex.raw.try_lock());
+        Ok(match locked {
+            true => Some(CustomMutexGuard { mutex: self }),
+            false => None,
+        })
+    }
+}
+impl<'a, T, M, const LEVEL: u32> FallibleMutex<'a> for CustomMutex<T, Leveled<M, LEVEL>>
+where
+    T: 'a,
+    M: RawMutex + 'a,
+{
+    fn lock_fallible(&'a self) -> Result<Self::Guard, Self::Error> {
+        self.raw_mutex.check()?;
+        self.raw_mutex.raw.lock();
+        self.raw_mutex.acquired(true);
+        Ok(CustomMutexGuard { mutex: self })
+    }
+}
+impl<'a, T, M, const LEVEL: u32> TimeoutFallibleMutex<'a> for CustomMutex<T, Leveled<M, LEVEL>>
+where
+    T: 'a,
+    M: RawTimeoutMutex + 'a,
+{
+    fn lock_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::Guard>, Self::Error> {
+        self.raw_mutex.check()?;
+        let locked = self
+            .raw_mutex
+            .acquired(self.raw_mutex.raw.lock_timeout(timeout));
+        Ok(match locked {
+            true => Some(CustomMutexGuard { mutex: self }),
+            false => None,
+        })
+    }
+}
+impl<'a, T, R, const LEVEL: u32> TryFallibleRwLock<'a> for CustomRwLock<T, Leveled<R, LEVEL>>
+where
+    T: 'a,
+    R: RawTryRwLock + 'a,
+{
+    type Item = T;
+    type ReadGuard = CustomReadGuard<'a, T, Leveled<R, LEVEL>>;
+    type WriteGuard = CustomWriteGuard<'a, T, Leveled<R, LEVEL>>;
+    type ReadError = LevelViolation;
+    type WriteError = LevelViolation;
+
+    fn try_read_fallible(&'a self) -> Result<Option<Self::ReadGuard>, Self::ReadError> {
+        self.raw_lock.check()?;
+        let locked = self.raw_lock.acquired(self.raw_lock.raw.try_add_reader());
+        Ok(match locked {
+            true => Some(CustomReadGuard { lock: self }),
+            false => None,
+        })
+    }
+
+    fn try_write_fallible(&'a self) -> Result<Option<Self::WriteGuard>, Self::WriteError> {
+        self.raw_lock.check()?;
+        let locked = self.raw_lock.acquired(self.raw_lock.raw.try_add_writer());
+        Ok(match locked {
+            true => Some(CustomWriteGuard { lock: self }),
+            false => None,
+        })
+    }
+}
+impl<'a, T, R, const LEVEL: u32> FallibleRwLock<'a> for CustomRwLock<T, Leveled<R, LEVEL>>
+where
+    T: 'a,
+    R: RawRwLock + 'a,
+{
+    fn read_fallible(&'a self) -> Result<Self::ReadGuard, Self::ReadError> {
+        self.raw_lock.check()?;
+        self.raw_lock.raw.add_reader();
+        self.raw_lock.acquired(true);
+        Ok(CustomReadGuard { lock: self })
+    }
+
+    fn write_fallible(&'a self) -> Result<Self::WriteGuard, Self::WriteError> {
+        self.raw_lock.check()?;
+        self.raw_lock.raw.add_writer();
+        self.raw_lock.acquired(true);
+        Ok(CustomWriteGuard { lock: self })
+    }
+}
+impl<'a, T, R, const LEVEL: u32> TimeoutFallibleRwLock<'a> for CustomRwLock<T, Leveled<R, LEVEL>>
+where
+    T: 'a,
+    R: RawTimeoutRwLock + 'a,
+{
+    fn read_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadGuard>, Self::ReadError> {
+        self.raw_lock.check()?;
+        let locked = self
+            .raw_lock
+            .acquired(self.raw_lock.raw.add_reader_timeout(timeout));
+        Ok(match locked {
+            true => Some(CustomReadGuard { lock: self }),
+            false => None,
+        })
+    }
+
+    fn write_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteGuard>, Self::WriteError> {
+        self.raw_lock.check()?;
+        let locked = self
+            .raw_lock
+            .acquired(self.raw_lock.raw.add_writer_timeout(timeout));
+        Ok(match locked {
+            true => Some(CustomWriteGuard { lock: self }),
+            false => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(all(debug_assertions, feature = "std"))]
+    use crate::leveled::{LevelViolation, LeveledMutex, LeveledRwLock};
+    #[cfg(all(debug_assertions, feature = "std"))]
+    use crate::mutex::{FallibleMutex, Mutex, RawSpinLock, TryMutex};
+    #[cfg(all(debug_assertions, feature = "std"))]
+    use crate::rw_lock::{RawSpinRwLock, RwLock, TryFallibleRwLock};
+    #[cfg(all(debug_assertions, feature = "std"))]
+    use crate::StdThreadFunctions;
+    #[cfg(all(debug_assertions, feature = "std"))]
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    #[cfg(all(debug_assertions, feature = "std"))]
+    use std::thread::scope;
+
+    #[cfg(all(debug_assertions, feature = "std"))]
+    #[test]
+    fn leveled_test() {
+        let low = LeveledMutex::<_, RawSpinLock<StdThreadFunctions>, 1>::new(0);
+        let high = LeveledRwLock::<_, RawSpinRwLock<StdThreadFunctions>, 2>::new(0);
+
+        let low_guard = low.lock();
+        let high_guard = high.read();
+        drop(low_guard);
+        drop(high_guard);
+
+        let high_guard = high.write();
+        assert_eq!(
+            low.lock_fallible().err(),
+            Some(LevelViolation { level: 1, held: 2 })
+        );
+        assert!(catch_unwind(AssertUnwindSafe(|| low.try_lock())).is_err());
+        drop(high_guard);
+        // The failed attempts did not lock
+        let low_guard = low.try_lock().expect("Could not lock");
+        assert!(high.try_read_fallible().expect("Level violated").is_some());
+        // Releasing on a thread that does not hold the level is caught
+        assert!(scope(|scope| scope.spawn(move || drop(low_guard)).join()).is_err());
+    }
+}
@@ -7,11 +7,27 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+pub mod backoff;
+#[cfg(feature = "alloc")]
+pub mod blocking_bridge;
+pub mod condvar;
+#[cfg(feature = "deadlock_detection")]
+pub mod deadlock;
+pub mod leveled;
 pub mod mutex;
+#[cfg(feature = "std")]
+pub mod poison;
 pub mod queue;
 pub mod rw_lock;
 pub mod semaphore;
 pub mod stack;
+#[cfg(feature = "std")]
+mod timer;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use async_trait::async_trait;
 
 use core::convert::Infallible;
 use core::fmt::Debug;
@@ -21,6 +37,14 @@ use core::time::Duration;
 trait EnsureSend: Send {}
 trait EnsureSync: Sync {}
 
+/// Runs the function when dropped, including while unwinding.
+struct OnDrop<F: FnMut()>(F);
+impl<F: FnMut()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
+
 /// Functions to interact with system time.
 pub trait TimeFunctions {
     /// The type of an instant for this system. Analog for [`std::time::Instant`].
@@ -35,6 +59,15 @@ pub trait TimeFunctions {
     /// Get the current instant. Analog for [`std::time::Instant::now`].
     fn current_time() -> Self::InstantType;
 }
+/// Functions to sleep asynchronously. Async analog for [`ThreadFunctions::sleep`].
+#[cfg(feature = "alloc")]
+#[async_trait]
+pub trait AsyncTimeFunctions: TimeFunctions {
+    /// Completes after a specified duration.
+    async fn sleep_async(duration: Duration);
+    /// Completes once a given instant has been reached.
+    async fn sleep_until_async(instant: Self::InstantType);
+}
 /// Functions to allow the current thread to interact in ways a thread might need to.
 pub trait ThreadFunctions {
     /// Sleeps the current thread for a specified duration. Analog for [`std::thread::sleep`].
@@ -174,12 +207,48 @@ where
 }
 
 /// Std implementations for [`TimeFunctions`], [`ThreadFunctions], [`TryThreadSpawner`], and [`ThreadParker`].
+/// [`AsyncTimeFunctions`] is driven by a shared background timer thread started on first use.
 #[cfg(feature = "std")]
 #[derive(Copy, Clone, Debug)]
 pub struct StdThreadFunctions;
+/// A [`std::thread::Thread`] that is compared by its [`std::thread::ThreadId`].
+/// Used as the [`ThreadParker::ThreadId`] of [`StdThreadFunctions`].
+#[cfg(feature = "std")]
+#[repr(transparent)]
+#[derive(Clone, Debug)]
+pub struct StdThread(pub std::thread::Thread);
 #[cfg(feature = "std")]
 mod std_thread_impls {
     use super::*;
+    use core::hash::{Hash, Hasher};
+    use core::ops::Deref;
+
+    impl Deref for StdThread {
+        type Target = std::thread::Thread;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+    impl From<std::thread::Thread> for StdThread {
+        #[inline]
+        fn from(from: std::thread::Thread) -> Self {
+            Self(from)
+        }
+    }
+    impl PartialEq for StdThread {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+            self.0.id() == other.0.id()
+        }
+    }
+    impl Eq for StdThread {}
+    impl Hash for StdThread {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.id().hash(state)
+        }
+    }
     impl TimeFunctions for StdThreadFunctions {
         type InstantType = std::time::Instant;
 
@@ -188,6 +257,16 @@ mod std_thread_impls {
             std::time::Instant::now()
         }
     }
+    #[async_trait]
+    impl AsyncTimeFunctions for StdThreadFunctions {
+        async fn sleep_async(duration: Duration) {
+            crate::timer::sleep_until(std::time::Instant::now() + duration).await
+        }
+
+        async fn sleep_until_async(instant: Self::InstantType) {
+            crate::timer::sleep_until(instant).await
+        }
+    }
     impl ThreadFunctions for StdThreadFunctions {
         #[inline]
         fn sleep(duration: Duration) {
@@ -212,7 +291,7 @@ mod std_thread_impls {
         }
     }
     impl ThreadParker for StdThreadFunctions {
-        type ThreadId = std::thread::Thread;
+        type ThreadId = StdThread;
 
         #[inline]
         fn park() {
@@ -221,12 +300,12 @@ mod std_thread_impls {
 
         #[inline]
         fn unpark(thread: Self::ThreadId) {
-            thread.unpark()
+            thread.0.unpark()
         }
 
         #[inline]
         fn current_thread() -> Self::ThreadId {
-            std::thread::current()
+            StdThread(std::thread::current())
         }
     }
     impl ThreadTimeoutParker for StdThreadFunctions {
@@ -235,11 +314,12 @@ mod std_thread_impls {
         }
     }
     impl<O> ThreadHandle for std::thread::JoinHandle<O> {
-        type ThreadId = std::thread::Thread;
+        type ThreadId = StdThread;
 
         #[inline]
         fn thread_id(&self) -> &Self::ThreadId {
-            self.thread()
+            // Safe because `StdThread` is `repr(transparent)`
+            unsafe { &*(self.thread() as *const std::thread::Thread as *const StdThread) }
         }
     }
     impl<O> TryJoinableHandle for std::thread::JoinHandle<O> {
@@ -0,0 +1,135 @@
+use crate::mutex::{CustomMutex, RawAsyncMutex, RawMutex, RawTryMutex};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use async_trait::async_trait;
+use core::ops::{Deref, DerefMut};
+
+/// A mutex in an [`Arc`] that can give owned guards, non-blocking.
+pub trait TryArcMutex {
+    /// The item stored in the mutex
+    type Item: ?Sized;
+    /// The owned guard for the mutex
+    type ArcGuard: DerefMut<Target = Self::Item>;
+
+    /// Tries to lock the mutex, returning `None` if not possible.
+    fn try_lock_arc(self: &Arc<Self>) -> Option<Self::ArcGuard>;
+}
+/// A mutex in an [`Arc`] that can give owned guards, blocking.
+pub trait ArcMutex: TryArcMutex {
+    /// Locks the mutex, blocking until successful
+    fn lock_arc(self: &Arc<Self>) -> Self::ArcGuard;
+}
+/// A mutex in an [`Arc`] that can give owned guards asynchronously.
+#[async_trait]
+pub trait AsyncArcMutex: TryArcMutex {
+    /// Locks the mutex asynchronously, returning a future with the guard.
+    async fn lock_async_arc(self: &Arc<Self>) -> Self::ArcGuard;
+}
+
+impl<T, M> TryArcMutex for CustomMutex<T, M>
+where
+    M: RawTryMutex,
+{
+    type Item = T;
+    type ArcGuard = ArcCustomMutexGuard<T, M>;
+
+    fn try_lock_arc(self: &Arc<Self>) -> Option<Self::ArcGuard> {
+        match self.raw_mutex.try_lock() {
+            true => Some(ArcCustomMutexGuard {
+                mutex: self.clone(),
+            }),
+            false => None,
+        }
+    }
+}
+impl<T, M> ArcMutex for CustomMutex<T, M>
+where
+    M: RawMutex,
+{
+    fn lock_arc(self: &Arc<Self>) -> Self::ArcGuard {
+        self.raw_mutex.lock();
+        ArcCustomMutexGuard {
+            mutex: self.clone(),
+        }
+    }
+}
+#[async_trait]
+impl<T, M> AsyncArcMutex for CustomMutex<T, M>
+where
+    T: Send,
+    M: RawAsyncMutex + Send + Sync,
+{
+    async fn lock_async_arc(self: &Arc<Self>) -> Self::ArcGuard {
+        self.raw_mutex.lock_async().await;
+        ArcCustomMutexGuard {
+            mutex: self.clone(),
+        }
+    }
+}
+
+/// An owned guard for a [`CustomMutex`] in an [`Arc`]
+#[derive(Debug)]
+pub struct ArcCustomMutexGuard<T, M>
+where
+    M: RawTryMutex,
+{
+    mutex: Arc<CustomMutex<T, M>>,
+}
+impl<T, M> ArcCustomMutexGuard<T, M>
+where
+    M: RawTryMutex,
+{
+    /// Gets the mutex this guard is for
+    pub fn mutex(guard: &Self) -> &Arc<CustomMutex<T, M>> {
+        &guard.mutex
+    }
+}
+impl<T, M> Deref for ArcCustomMutexGuard<T, M>
+where
+    M: RawTryMutex,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+impl<T, M> DerefMut for ArcCustomMutexGuard<T, M>
+where
+    M: RawTryMutex,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+impl<T, M> Drop for ArcCustomMutexGuard<T, M>
+where
+    M: RawTryMutex,
+{
+    fn drop(&mut self) {
+        unsafe { self.mutex.raw_mutex.unlock() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{ArcMutex, SpinLockStd, TryArcMutex};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn arc_mutex_test() {
+        let mutex = Arc::new(SpinLockStd::new(0usize));
+        let mut guard = mutex.lock_arc();
+        assert!(mutex.try_lock_arc().is_none());
+        let handle = spawn(move || {
+            *guard += 1;
+        });
+        handle.join().expect("Could not join");
+        assert_eq!(*mutex.try_lock_arc().expect("Could not lock"), 1);
+    }
+}
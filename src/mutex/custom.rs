@@ -6,8 +6,8 @@ use core::ops::{Deref, DerefMut};
 /// A Mutex based on a given [`RawTryMutex`]
 #[derive(Debug)]
 pub struct CustomMutex<T, M: ?Sized> {
-    pub(super) data: UnsafeCell<T>,
-    pub(super) raw_mutex: M,
+    pub(crate) data: UnsafeCell<T>,
+    pub(crate) raw_mutex: M,
 }
 impl<T, M> CustomMutex<T, M> {
     /// Creates a new `CustomMutex` with a `RawMutex`
@@ -63,7 +63,7 @@ pub struct CustomMutexGuard<'a, T, M>
 where
     M: RawTryMutex,
 {
-    pub(super) mutex: &'a CustomMutex<T, M>,
+    pub(crate) mutex: &'a CustomMutex<T, M>,
 }
 impl<'a, T, M> Deref for CustomMutexGuard<'a, T, M>
 where
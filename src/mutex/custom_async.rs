@@ -1,16 +1,26 @@
+use crate::blocking_bridge::{BlockingBridge, BridgeMessage, MessageSender};
 use crate::mutex::*;
 use crate::queue::Queue;
 use crate::TryThreadSpawner;
 use alloc::boxed::Box;
 use alloc::sync::{Arc, Weak};
 use async_trait::async_trait;
+#[cfg(feature = "std")]
+use core::any::Any;
+#[cfg(feature = "std")]
+use core::time::Duration;
 use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
+#[cfg(feature = "std")]
+use simple_futures::value_future::{ValueFuture, ValueFutureHandle};
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 /// A Custom mutex with async operations based on a [`RawMutex`].
 pub type CustomAsyncMutex<T, M, Q> = CustomMutex<T, RawCustomAsyncMutex<M, Q>>;
 
 /// A converter for turning a [`RawMutex`] into a [`RawAsyncMutex`]. Runs all
-/// operations on own task.
+/// operations on own task, or on a shared [`BlockingBridge`] if created with
+/// [`RawCustomAsyncMutex::from_bridge`].
 #[derive(Debug)]
 pub struct RawCustomAsyncMutex<M, Q> {
     inner: Arc<RawCustomAsyncMutexInner<M, Q>>,
@@ -44,23 +54,43 @@ where
 
     fn thread_function(inner: Weak<RawCustomAsyncMutexInner<M, Q>>) {
         while let Some(inner) = inner.upgrade() {
-            match inner.message_queue.pop() {
-                RawCustomAsyncMutexMessage::Lock(future) => {
-                    inner.raw_mutex.lock();
-                    match future.complete() {
-                        None => unsafe { inner.raw_mutex.unlock() },
-                        Some(true) => panic!("Future was completed already!"),
-                        Some(false) => {}
-                    }
-                } // RawCustomAsyncMutexMessage::LockTimeout { .. } => unreachable!(),
-            }
+            let message = inner.message_queue.pop();
+            inner.handle_message(message);
         }
     }
 }
+impl<M, Q> RawCustomAsyncMutex<M, BlockingBridge<Q>>
+where
+    M: 'static + RawMutex + Send + Sync,
+    Q: 'static + Queue<Item = BridgeMessage> + Send + Sync,
+{
+    /// Creates a new [`RawCustomAsyncMutex`] from a [`RawMutex`] that sends its
+    /// operations to a shared [`BlockingBridge`] rather than its own thread.
+    pub fn from_bridge(raw_mutex: M, bridge: BlockingBridge<Q>) -> Self {
+        Self {
+            inner: Arc::new(RawCustomAsyncMutexInner {
+                raw_mutex,
+                message_queue: bridge,
+            }),
+        }
+    }
+}
+impl<M, Q> RawCustomAsyncMutex<M, Q>
+where
+    M: 'static + RawMutex + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncMutexMessage> + Send + Sync,
+{
+    fn send(&self, message: RawCustomAsyncMutexMessage) {
+        let inner = self.inner.clone();
+        self.inner
+            .message_queue
+            .send(message, move |message| inner.handle_message(message));
+    }
+}
 unsafe impl<M, Q> RawTryMutex for RawCustomAsyncMutex<M, Q>
 where
     M: 'static + RawMutex + Send + Sync,
-    Q: 'static + Queue<Item = RawCustomAsyncMutexMessage> + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncMutexMessage> + Send + Sync,
 {
     #[inline]
     fn try_lock(&self) -> bool {
@@ -75,7 +105,7 @@ where
 unsafe impl<M, Q> RawMutex for RawCustomAsyncMutex<M, Q>
 where
     M: 'static + RawMutex + Send + Sync,
-    Q: 'static + Queue<Item = RawCustomAsyncMutexMessage> + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncMutexMessage> + Send + Sync,
 {
     #[inline]
     fn lock(&self) {
@@ -86,35 +116,182 @@ where
 unsafe impl<M, Q> RawAsyncMutex for RawCustomAsyncMutex<M, Q>
 where
     M: 'static + RawMutex + Send + Sync,
-    Q: 'static + Queue<Item = RawCustomAsyncMutexMessage> + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncMutexMessage> + Send + Sync,
 {
     async fn lock_async(&self) {
         let future = CompleteFuture::new();
-        self.inner
-            .message_queue
-            .try_push(RawCustomAsyncMutexMessage::Lock(future.get_handle()))
-            .unwrap_or_else(|_| panic!("Could not add to message queue"));
+        self.send(RawCustomAsyncMutexMessage::Lock(future.get_handle()));
         future.await;
     }
 }
+#[cfg(feature = "std")]
+#[async_trait]
+unsafe impl<M, Q> RawAsyncTimeoutMutex for RawCustomAsyncMutex<M, Q>
+where
+    M: 'static + RawTimeoutMutex + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncMutexMessage> + Send + Sync,
+{
+    async fn lock_timeout_async(&self, timeout: Duration) -> bool {
+        let future = ValueFuture::new();
+        self.send(RawCustomAsyncMutexMessage::LockTimeout {
+            future: future.get_handle(),
+            start: Instant::now(),
+            timeout,
+            lock_timeout: |raw_mutex, timeout| {
+                raw_mutex
+                    .downcast_ref::<M>()
+                    .expect("Message sent to wrong mutex")
+                    .lock_timeout(timeout)
+            },
+        });
+        future.await
+    }
+}
 
 #[derive(Debug)]
 struct RawCustomAsyncMutexInner<M, Q> {
     raw_mutex: M,
     message_queue: Q,
 }
+impl<M, Q> RawCustomAsyncMutexInner<M, Q>
+where
+    M: 'static + RawMutex,
+{
+    fn handle_message(&self, message: RawCustomAsyncMutexMessage) {
+        match message {
+            RawCustomAsyncMutexMessage::Lock(future) => {
+                self.raw_mutex.lock();
+                match future.complete() {
+                    None => unsafe { self.raw_mutex.unlock() },
+                    Some(true) => panic!("Future was completed already!"),
+                    Some(false) => {}
+                }
+            }
+            #[cfg(feature = "std")]
+            RawCustomAsyncMutexMessage::LockTimeout {
+                future,
+                start,
+                timeout,
+                lock_timeout,
+            } => {
+                let remaining = timeout.saturating_sub(start.elapsed());
+                let locked = lock_timeout(&self.raw_mutex, remaining);
+                match future.assign(locked) {
+                    None if locked => unsafe { self.raw_mutex.unlock() },
+                    None => {}
+                    Some(Err(_)) => panic!("Future was assigned already!"),
+                    Some(Ok(())) => {}
+                }
+            }
+        }
+    }
+}
 /// The message used for [`RawCustomAsyncMutex`]
 #[derive(Debug)]
 pub enum RawCustomAsyncMutexMessage {
     /// A lock operation
     Lock(CompleteFutureHandle),
-    // /// Not currently used but reserved for future designs
-    // LockTimeout {
-    //     /// The future the timeout is for
-    //     future: ValueFutureHandle<bool>,
-    //     /// When the timeout was called
-    //     start: Instant,
-    //     /// How long the timeout is for
-    //     timeout: Duration,
-    // },
+    /// A lock operation with a timeout, only sent when the raw mutex is a [`RawTimeoutMutex`]
+    #[cfg(feature = "std")]
+    LockTimeout {
+        /// The future the timeout is for, assigned true if locked
+        future: ValueFutureHandle<bool>,
+        /// When the timeout was called
+        start: Instant,
+        /// How long the timeout is for
+        timeout: Duration,
+        /// Calls [`RawTimeoutMutex::lock_timeout`] on the converter's raw mutex, which is passed erased
+        /// as the converter does not require [`RawTimeoutMutex`]
+        lock_timeout: fn(&dyn Any, Duration) -> bool,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::blocking_bridge::BlockingBridge;
+    #[cfg(feature = "std")]
+    use crate::mutex::{
+        AsyncMutex, AsyncTimeoutMutex, CustomAsyncMutex, RawCustomAsyncMutex, RawParkMutexStd,
+        TryMutex,
+    };
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::future::Future;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::task::{Context, Poll, Wake};
+    #[cfg(feature = "std")]
+    use std::thread::{current, park, Thread};
+    #[cfg(feature = "std")]
+    use std::time::{Duration, Instant};
+
+    #[cfg(feature = "std")]
+    struct ThreadWaker(Thread);
+    #[cfg(feature = "std")]
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+    #[cfg(feature = "std")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(ThreadWaker(current())).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut context) {
+                return out;
+            }
+            park();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lock_timeout_async_test() {
+        let (raw, _handle) = RawCustomAsyncMutex::new::<StdThreadFunctions>(
+            RawParkMutexStd::default(),
+            ParkQueueStd::default(),
+        )
+        .expect("Could not spawn");
+        let mutex: CustomAsyncMutex<_, _, _> = CustomAsyncMutex::from_raw(raw, 0usize);
+        let guard = mutex.try_lock().expect("Could not lock");
+        let start = Instant::now();
+        assert!(block_on(mutex.lock_timeout_async(Duration::from_millis(50))).is_none());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        drop(guard);
+        assert!(block_on(mutex.lock_timeout_async(Duration::from_millis(50))).is_some());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn bridge_test() {
+        let (bridge, handles) =
+            BlockingBridge::new::<StdThreadFunctions>(2, ParkQueueStd::default())
+                .expect("Could not spawn");
+        let mutexes: Vec<CustomAsyncMutex<_, _, _>> = (0..10)
+            .map(|index| {
+                CustomAsyncMutex::from_raw(
+                    RawCustomAsyncMutex::from_bridge(RawParkMutexStd::default(), bridge.clone()),
+                    index,
+                )
+            })
+            .collect();
+        drop(bridge);
+        for (index, mutex) in mutexes.iter().enumerate() {
+            let guard = mutex.try_lock().expect("Could not lock");
+            assert!(block_on(mutex.lock_timeout_async(Duration::from_millis(10))).is_none());
+            drop(guard);
+            assert_eq!(*block_on(mutex.lock_async()), index);
+        }
+        drop(mutexes);
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+    }
 }
@@ -0,0 +1,282 @@
+use crate::mutex::{
+    Mutex, RawMutex, RawTimeoutMutex, RawTryMutex, ReentrantMutex, ReentrantMutexSized, SpinLock,
+    TimeoutReentrantMutex, TimeoutReentrantMutexSized, TryReentrantMutex, TryReentrantMutexSized,
+};
+use crate::{ThreadFunctions, ThreadParker};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::time::Duration;
+
+/// A reentrant mutex based on a given [`RawTryMutex`]. The owning thread is
+/// found with [`ThreadParker::current_thread`] and can lock the mutex again
+/// without blocking.
+#[derive(Debug)]
+pub struct CustomReentrantMutex<T, M, CS>
+where
+    CS: ThreadParker,
+{
+    data: UnsafeCell<T>,
+    raw_mutex: M,
+    owner: SpinLock<Option<CS::ThreadId>, CS>,
+    /// Only accessed by the owner
+    count: UnsafeCell<usize>,
+}
+impl<T, M, CS> CustomReentrantMutex<T, M, CS>
+where
+    CS: ThreadParker,
+{
+    /// Creates a new `CustomReentrantMutex` with a `RawMutex`
+    pub fn from_raw(raw_mutex: M, data: T) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+            raw_mutex,
+            owner: SpinLock::new(None),
+            count: UnsafeCell::new(0),
+        }
+    }
+
+    /// Creates a new reentrant mutex with a default raw
+    pub fn new(data: T) -> Self
+    where
+        M: Default,
+    {
+        Self::from_raw(M::default(), data)
+    }
+}
+impl<T, M, CS> CustomReentrantMutex<T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Eq,
+{
+    /// Adds a level if already owned by the current thread, otherwise uses
+    /// `lock_raw` to try to lock the raw mutex. Returns true if locked.
+    fn lock_with(&self, lock_raw: impl FnOnce() -> bool) -> bool {
+        let current = CS::current_thread();
+        if self.owner.lock().as_ref() == Some(&current) {
+            unsafe { *self.count.get() += 1 }
+            return true;
+        }
+        if !lock_raw() {
+            return false;
+        }
+        *self.owner.lock() = Some(current);
+        unsafe { *self.count.get() = 1 }
+        true
+    }
+}
+impl<T, M, CS> Default for CustomReentrantMutex<T, M, CS>
+where
+    T: Default,
+    M: Default,
+    CS: ThreadParker,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+impl<T, M, CS> From<T> for CustomReentrantMutex<T, M, CS>
+where
+    M: Default,
+    CS: ThreadParker,
+{
+    fn from(from: T) -> Self {
+        Self::new(from)
+    }
+}
+unsafe impl<T, M, CS> Send for CustomReentrantMutex<T, M, CS>
+where
+    T: Send,
+    M: Send,
+    CS: ThreadParker,
+    CS::ThreadId: Send,
+{
+}
+unsafe impl<T, M, CS> Sync for CustomReentrantMutex<T, M, CS>
+where
+    T: Send,
+    M: Sync,
+    CS: ThreadParker,
+    CS::ThreadId: Send,
+{
+}
+impl<'a, T, M, CS> TryReentrantMutex<'a> for CustomReentrantMutex<T, M, CS>
+where
+    T: 'a,
+    M: RawTryMutex + 'a,
+    CS: ThreadParker + ThreadFunctions + 'a,
+    CS::ThreadId: Eq,
+{
+    type Item = T;
+    type Guard = CustomReentrantMutexGuard<'a, T, M, CS>;
+
+    fn try_lock(&'a self) -> Option<Self::Guard> {
+        match self.lock_with(|| self.raw_mutex.try_lock()) {
+            true => Some(CustomReentrantMutexGuard::new(self)),
+            false => None,
+        }
+    }
+}
+impl<'a, T, M, CS> TryReentrantMutexSized<'a> for CustomReentrantMutex<T, M, CS>
+where
+    T: 'a,
+    M: RawTryMutex + 'a,
+    CS: ThreadParker + ThreadFunctions + 'a,
+    CS::ThreadId: Eq,
+{
+}
+impl<'a, T, M, CS> ReentrantMutex<'a> for CustomReentrantMutex<T, M, CS>
+where
+    T: 'a,
+    M: RawMutex + 'a,
+    CS: ThreadParker + ThreadFunctions + 'a,
+    CS::ThreadId: Eq,
+{
+    fn lock(&'a self) -> Self::Guard {
+        self.lock_with(|| {
+            self.raw_mutex.lock();
+            true
+        });
+        CustomReentrantMutexGuard::new(self)
+    }
+}
+impl<'a, T, M, CS> ReentrantMutexSized<'a> for CustomReentrantMutex<T, M, CS>
+where
+    T: 'a,
+    M: RawMutex + 'a,
+    CS: ThreadParker + ThreadFunctions + 'a,
+    CS::ThreadId: Eq,
+{
+}
+impl<'a, T, M, CS> TimeoutReentrantMutex<'a> for CustomReentrantMutex<T, M, CS>
+where
+    T: 'a,
+    M: RawTimeoutMutex + 'a,
+    CS: ThreadParker + ThreadFunctions + 'a,
+    CS::ThreadId: Eq,
+{
+    fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard> {
+        match self.lock_with(|| self.raw_mutex.lock_timeout(timeout)) {
+            true => Some(CustomReentrantMutexGuard::new(self)),
+            false => None,
+        }
+    }
+}
+impl<'a, T, M, CS> TimeoutReentrantMutexSized<'a> for CustomReentrantMutex<T, M, CS>
+where
+    T: 'a,
+    M: RawTimeoutMutex + 'a,
+    CS: ThreadParker + ThreadFunctions + 'a,
+    CS::ThreadId: Eq,
+{
+}
+
+/// A guard for a [`CustomReentrantMutex`]. Must be dropped on the thread that
+/// created it so is not [`Send`].
+#[derive(Debug)]
+pub struct CustomReentrantMutexGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    mutex: &'a CustomReentrantMutex<T, M, CS>,
+    phantom_not_send: PhantomData<*const ()>,
+}
+impl<'a, T, M, CS> CustomReentrantMutexGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn new(mutex: &'a CustomReentrantMutex<T, M, CS>) -> Self {
+        Self {
+            mutex,
+            phantom_not_send: PhantomData,
+        }
+    }
+}
+impl<'a, T, M, CS> Deref for CustomReentrantMutexGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+impl<'a, T, M, CS> Drop for CustomReentrantMutexGuard<'a, T, M, CS>
+where
+    M: RawTryMutex,
+    CS: ThreadParker + ThreadFunctions,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let count = &mut *self.mutex.count.get();
+            *count -= 1;
+            if *count == 0 {
+                *self.mutex.owner.lock() = None;
+                self.mutex.raw_mutex.unlock();
+            }
+        }
+    }
+}
+unsafe impl<'a, T, M, CS> Sync for CustomReentrantMutexGuard<'a, T, M, CS>
+where
+    T: Sync,
+    M: RawTryMutex + Sync,
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Send,
+{
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{
+        CustomReentrantMutex, RawParkMutexStd, ReentrantMutex, TimeoutReentrantMutex,
+        TryReentrantMutex,
+    };
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::cell::Cell;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn custom_reentrant_mutex_test() {
+        let mutex = Arc::new(
+            CustomReentrantMutex::<_, RawParkMutexStd, StdThreadFunctions>::new(Cell::new(0)),
+        );
+        let guard = mutex.lock();
+        let inner_guard = mutex.lock();
+        let timeout_guard = mutex
+            .lock_timeout(Duration::from_millis(10))
+            .expect("Could not relock");
+        inner_guard.set(inner_guard.get() + 1);
+
+        let mutex_clone = mutex.clone();
+        spawn(move || assert!(mutex_clone.try_lock().is_none()))
+            .join()
+            .expect("Could not join");
+        let mutex_clone = mutex.clone();
+        let handle = spawn(move || {
+            let guard = mutex_clone.lock();
+            guard.set(guard.get() + 1);
+        });
+        drop(timeout_guard);
+        drop(inner_guard);
+        assert_eq!(guard.get(), 1);
+        drop(guard);
+        handle.join().expect("Could not join");
+        assert_eq!(mutex.lock().get(), 2);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::mutex::{CustomMutexGuard, RawTryMutex};
+use core::mem::ManuallyDrop;
+
+/// A raw mutex that can hand its lock directly to a waiting thread.
+///
+/// # Safety
+/// [`RawFairMutex::unlock_fair`] must uphold the same guarantees as
+/// [`RawTryMutex::unlock`].
+pub unsafe trait RawFairMutex: RawTryMutex {
+    /// Unlocks the mutex, passing the lock to a waiting thread if there is one
+    /// rather than letting other threads take it first.
+    ///
+    /// # Safety
+    /// Same as [`RawTryMutex::unlock`].
+    unsafe fn unlock_fair(&self);
+}
+
+impl<'a, T, M> CustomMutexGuard<'a, T, M>
+where
+    M: RawFairMutex,
+{
+    /// Unlocks the mutex with [`RawFairMutex::unlock_fair`].
+    pub fn unlock_fair(guard: Self) {
+        let guard = ManuallyDrop::new(guard);
+        unsafe { guard.mutex.raw_mutex.unlock_fair() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{CustomMutexGuard, Mutex, ParkMutexStd};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::{sleep, spawn};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn unlock_fair_test() {
+        let mutex = Arc::new(ParkMutexStd::new(Vec::new()));
+        let guard = mutex.lock();
+        let mutex_clone = mutex.clone();
+        let handle = spawn(move || mutex_clone.lock().push(1));
+        sleep(Duration::from_millis(50));
+        CustomMutexGuard::unlock_fair(guard);
+        // The lock was handed to the waiting thread so this must come second
+        mutex.lock().push(2);
+        handle.join().expect("Could not join");
+        assert_eq!(*mutex.lock(), vec![1, 2]);
+    }
+}
@@ -0,0 +1,29 @@
+use core::ops::DerefMut;
+use core::time::Duration;
+
+/// A non-blocking mutex whose locking can fail, such as by being poisoned.
+pub trait TryFallibleMutex<'a> {
+    /// The item stored in the mutex
+    type Item: ?Sized;
+    /// The guard for the mutex
+    type Guard: DerefMut<Target = Self::Item>;
+    /// The error returned when locking fails
+    type Error;
+
+    /// Tries to lock the mutex, returning `Ok(None)` if not possible.
+    fn try_lock_fallible(&'a self) -> Result<Option<Self::Guard>, Self::Error>;
+}
+/// A mutex whose locking can fail, such as by being poisoned.
+pub trait FallibleMutex<'a>: TryFallibleMutex<'a> {
+    /// Locks the mutex, blocking until successful or failed
+    fn lock_fallible(&'a self) -> Result<Self::Guard, Self::Error>;
+}
+/// A mutex that can timeout for locking whose locking can fail.
+pub trait TimeoutFallibleMutex<'a>: TryFallibleMutex<'a> {
+    /// Locks the mutex blocking for timeout or until locked or failed.
+    /// Returns `Ok(None)` on timeout.
+    fn lock_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::Guard>, Self::Error>;
+}
@@ -0,0 +1,179 @@
+use crate::mutex::{
+    CustomMutex, RawFairMutex, RawMutex, RawParkMutex, RawTimeoutMutex, RawTryMutex,
+};
+use crate::{ThreadFunctions, ThreadParker, ThreadTimeoutParker, TimeFunctions};
+use core::hint::spin_loop;
+use core::time::Duration;
+
+/// An [`AdaptiveMutex`] that uses std functions.
+#[cfg(feature = "std")]
+pub type AdaptiveMutexStd<T> = AdaptiveMutex<T, crate::StdThreadFunctions>;
+
+/// A [`RawAdaptiveMutex`] that uses std functions.
+#[cfg(feature = "std")]
+pub type RawAdaptiveMutexStd = RawAdaptiveMutex<crate::StdThreadFunctions>;
+
+/// A mutex that spins for a short time before parking. Uses
+/// [`RawAdaptiveMutex`].
+pub type AdaptiveMutex<T, CS> = CustomMutex<T, RawAdaptiveMutex<CS>>;
+
+/// The raw portion of [`AdaptiveMutex`]. Tries to lock `spin_limit` times with
+/// exponential backoff before falling back to a [`RawParkMutex`].
+#[derive(Debug)]
+pub struct RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+{
+    mutex: RawParkMutex<CS>,
+    spin_limit: u32,
+}
+impl<CS> RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+{
+    /// The spin limit used by [`Default`]
+    pub const DEFAULT_SPIN_LIMIT: u32 = 10;
+    /// Backoff stops growing after this many spins
+    const MAX_BACKOFF_SHIFT: u32 = 6;
+
+    /// Creates a new mutex that tries `spin_limit` times before parking
+    pub fn with_spin_limit(spin_limit: u32) -> Self {
+        Self {
+            mutex: RawParkMutex::default(),
+            spin_limit,
+        }
+    }
+}
+impl<CS> RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+{
+    /// Spins trying to lock, returning true if locked.
+    fn spin(&self) -> bool {
+        for attempt in 0..self.spin_limit {
+            if self.mutex.try_lock() {
+                return true;
+            }
+            for _ in 0..1u32 << attempt.min(Self::MAX_BACKOFF_SHIFT) {
+                spin_loop();
+            }
+        }
+        false
+    }
+}
+impl<CS> Default for RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+{
+    fn default() -> Self {
+        Self::with_spin_limit(Self::DEFAULT_SPIN_LIMIT)
+    }
+}
+unsafe impl<CS> RawTryMutex for RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+{
+    #[inline]
+    fn try_lock(&self) -> bool {
+        self.mutex.try_lock()
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        self.mutex.unlock()
+    }
+}
+unsafe impl<CS> RawFairMutex for RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+{
+    #[inline]
+    unsafe fn unlock_fair(&self) {
+        self.mutex.unlock_fair()
+    }
+}
+unsafe impl<CS> RawMutex for RawAdaptiveMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Eq + Clone,
+{
+    fn lock(&self) {
+        if !self.spin() {
+            self.mutex.lock()
+        }
+    }
+
+    #[inline]
+    unsafe fn bump(&self) {
+        self.mutex.bump()
+    }
+}
+unsafe impl<CS> RawTimeoutMutex for RawAdaptiveMutex<CS>
+where
+    CS: ThreadTimeoutParker + TimeFunctions + ThreadFunctions,
+    CS::ThreadId: Eq + Clone,
+{
+    fn lock_timeout(&self, timeout: Duration) -> bool {
+        let end = CS::current_time() + timeout;
+        if self.spin() {
+            return true;
+        }
+        let current_time = CS::current_time();
+        if current_time >= end {
+            return false;
+        }
+        self.mutex.lock_timeout(end - current_time)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{AdaptiveMutexStd, Mutex, RawAdaptiveMutexStd, TimeoutMutex, TryMutex};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::{sleep, spawn};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn adaptive_mutex_test() {
+        let mutex = Arc::new(AdaptiveMutexStd::new(0usize));
+        let guard = mutex.lock();
+        assert!(mutex.try_lock().is_none());
+        assert!(mutex.lock_timeout(Duration::from_millis(10)).is_none());
+        let mutex_clone = mutex.clone();
+        let handle = spawn(move || {
+            *mutex_clone
+                .lock_timeout(Duration::from_secs(5))
+                .expect("Could not lock") += 1
+        });
+        sleep(Duration::from_millis(10));
+        drop(guard);
+        handle.join().expect("Could not join");
+
+        let mutex = Arc::new(AdaptiveMutexStd::from_raw(
+            RawAdaptiveMutexStd::with_spin_limit(0),
+            *mutex.lock(),
+        ));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mutex = mutex.clone();
+                spawn(move || {
+                    for _ in 0..1000 {
+                        *mutex.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(*mutex.lock(), 4001);
+    }
+}
@@ -1,11 +1,20 @@
-use crate::mutex::{CustomMutex, RawAsyncMutex, RawTryMutex};
+use crate::mutex::{
+    CustomMutex, RawAsyncMutex, RawAsyncTimeoutMutex, RawReadoutMutex, RawTryMutex,
+};
 use crate::queue::TryQueue;
+use crate::AsyncTimeFunctions;
 use alloc::boxed::Box;
 use async_trait::async_trait;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
 use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll};
+use core::time::Duration;
 use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
 
 /// A mutex that can only be accessed through async await or try operations.
+/// `CS` is only used to time out when it implements [`AsyncTimeFunctions`], defaulting to [`StdThreadFunctions`](crate::StdThreadFunctions) on std.
 /// ```
 /// # #[cfg(feature = "std")]
 /// # {
@@ -32,15 +41,21 @@ use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
 /// assert!(!future.as_mut().poll(&mut Context::from_waker(&Arc::new(NullWaker).into())).is_pending())
 /// # }
 /// ```
-pub type FullAsyncMutex<T, Q> = CustomMutex<T, RawFullAsyncMutex<Q>>;
+pub type FullAsyncMutex<T, Q, CS = DefaultCS> = CustomMutex<T, RawFullAsyncMutex<Q, CS>>;
+
+#[cfg(feature = "std")]
+type DefaultCS = crate::StdThreadFunctions;
+#[cfg(not(feature = "std"))]
+type DefaultCS = ();
 
 /// The raw portion of [`FullAsyncMutex`].
 #[derive(Debug)]
-pub struct RawFullAsyncMutex<Q> {
+pub struct RawFullAsyncMutex<Q, CS = DefaultCS> {
     locked: AtomicBool,
     waiting_queue: Q,
+    phantom_cs: PhantomData<fn() -> CS>,
 }
-impl<Q> Default for RawFullAsyncMutex<Q>
+impl<Q, CS> Default for RawFullAsyncMutex<Q, CS>
 where
     Q: Default,
 {
@@ -49,16 +64,17 @@ where
         Self::from(Q::default())
     }
 }
-impl<Q> From<Q> for RawFullAsyncMutex<Q> {
+impl<Q, CS> From<Q> for RawFullAsyncMutex<Q, CS> {
     #[inline]
     fn from(from: Q) -> Self {
         Self {
             locked: AtomicBool::new(false),
             waiting_queue: from,
+            phantom_cs: PhantomData,
         }
     }
 }
-unsafe impl<Q> RawTryMutex for RawFullAsyncMutex<Q>
+unsafe impl<Q, CS> RawTryMutex for RawFullAsyncMutex<Q, CS>
 where
     Q: TryQueue<Item = CompleteFutureHandle>,
 {
@@ -70,9 +86,8 @@ where
     unsafe fn unlock(&self) {
         loop {
             if let Some(handle) = self.waiting_queue.try_pop() {
-                let result = handle.complete();
-                if result.is_some() {
-                    debug_assert!(!result.unwrap());
+                // `None` if dropped and `Some(true)` if abandoned by a timeout
+                if let Some(false) = handle.complete() {
                     return;
                 }
             } else {
@@ -89,8 +104,17 @@ where
         }
     }
 }
+impl<Q, CS> RawReadoutMutex for RawFullAsyncMutex<Q, CS>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Acquire)
+    }
+}
 #[async_trait]
-unsafe impl<Q> RawAsyncMutex for RawFullAsyncMutex<Q>
+unsafe impl<Q, CS> RawAsyncMutex for RawFullAsyncMutex<Q, CS>
 where
     Q: TryQueue<Item = CompleteFutureHandle> + Sync,
 {
@@ -114,3 +138,123 @@ where
         future.await
     }
 }
+#[async_trait]
+unsafe impl<Q, CS> RawAsyncTimeoutMutex for RawFullAsyncMutex<Q, CS>
+where
+    Q: TryQueue<Item = CompleteFutureHandle> + Sync,
+    CS: AsyncTimeFunctions,
+{
+    async fn lock_timeout_async(&self, timeout: Duration) -> bool {
+        if self.try_lock() {
+            return true;
+        }
+        let mut future = CompleteFuture::new();
+        self.waiting_queue
+            .try_push(future.get_handle())
+            .expect("Could not push handle!");
+        // if was unlocked completely between try_lock and try_push
+        if self.try_lock() {
+            unsafe { self.unlock() }
+        }
+        let granted = WaitOrSleep {
+            wait: &mut future,
+            sleep: CS::sleep_async(timeout),
+        }
+        .await;
+        // Both `unlock` and this swap the future's completion state, so exactly one sees it
+        // unfinished. If `unlock` was first the lock was handed here, otherwise `unlock` will
+        // skip this abandoned handle.
+        granted || future.complete()
+    }
+}
+
+/// Waits on a [`CompleteFuture`] until a sleep finishes. Returns true if completed.
+struct WaitOrSleep<'a, S> {
+    wait: &'a mut CompleteFuture,
+    sleep: S,
+}
+impl<'a, S> Future for WaitOrSleep<'a, S>
+where
+    S: Future<Output = ()> + Unpin,
+{
+    type Output = bool;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Pin::new(&mut *self.wait).poll(cx).is_ready() {
+            Poll::Ready(true)
+        } else if Pin::new(&mut self.sleep).poll(cx).is_ready() {
+            Poll::Ready(false)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{AsyncTimeoutMutex, FullAsyncMutex, TryMutex};
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use std::future::Future;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::task::{Context, Poll, Wake};
+    #[cfg(feature = "std")]
+    use std::thread::{current, park_timeout, sleep, spawn, Thread};
+    #[cfg(feature = "std")]
+    use std::time::{Duration, Instant};
+
+    #[cfg(feature = "std")]
+    struct ThreadWaker(Thread);
+    #[cfg(feature = "std")]
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+    #[cfg(feature = "std")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(ThreadWaker(current())).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        let end = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut context) {
+                return out;
+            }
+            let now = Instant::now();
+            assert!(now < end, "Future was never woken");
+            park_timeout(end - now);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lock_timeout_async_test() {
+        let mutex = Arc::new(FullAsyncMutex::<_, ParkQueueStd<_>>::new(0usize));
+        let guard = mutex.try_lock().expect("Could not lock");
+        let start = Instant::now();
+        assert!(block_on(mutex.lock_timeout_async(Duration::from_millis(50))).is_none());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        drop(guard);
+        // The abandoned waiter must not keep the lock
+        assert!(mutex.try_lock().is_some());
+        assert!(block_on(mutex.lock_timeout_async(Duration::from_millis(50))).is_some());
+
+        // Handed over while waiting
+        let guard = mutex.try_lock().expect("Could not lock");
+        let mutex_clone = mutex.clone();
+        let handle = spawn(move || {
+            let mut guard = block_on(mutex_clone.lock_timeout_async(Duration::from_secs(5)))
+                .expect("Timed out");
+            *guard += 1;
+        });
+        sleep(Duration::from_millis(50));
+        drop(guard);
+        handle.join().expect("Could not join");
+        assert_eq!(*mutex.try_lock().expect("Could not lock"), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::mutex::{CustomMutex, RawTryMutex};
+use crate::mutex::{CustomMutex, RawReadoutMutex, RawTryMutex};
 use crate::{EnsureSend, EnsureSync};
 use core::sync::atomic::{AtomicBool, Ordering};
 
@@ -34,5 +34,11 @@ unsafe impl RawTryMutex for RawAtomicMutex {
         }
     }
 }
+impl RawReadoutMutex for RawAtomicMutex {
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Acquire)
+    }
+}
 impl EnsureSend for RawAtomicMutex {}
 impl EnsureSync for RawAtomicMutex {}
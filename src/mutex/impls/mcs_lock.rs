@@ -0,0 +1,166 @@
+use crate::backoff::{Backoff, DefaultBackoff};
+use crate::mutex::{CustomMutex, RawMutex, RawTimeoutMutex, RawTryMutex};
+use crate::TimeFunctions;
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use core::time::Duration;
+
+/// A [`McsLock`] that uses std functions.
+#[cfg(feature = "std")]
+pub type McsLockStd<T> = McsLock<T, crate::StdThreadFunctions>;
+
+/// A queue based spin lock. Each waiter spins on its own node so contention
+/// does not bounce a single cache line between cores. Should only be locked for
+/// short operations.
+/// Waits between attempts with `B`.
+pub type McsLock<T, CS, B = DefaultBackoff<CS>> = CustomMutex<T, RawMcsLock<CS, B>>;
+/// The raw portion of [`McsLock`].
+///
+/// Timeouts do not join the queue as an abandoned node would block every later
+/// one, so timed out locks are not served in order.
+#[derive(Debug)]
+pub struct RawMcsLock<CS, B = DefaultBackoff<CS>> {
+    /// Last node in the queue, null if unlocked
+    tail: AtomicPtr<McsNode>,
+    /// Node of the current holder, only accessed by the holder
+    owner: AtomicPtr<McsNode>,
+    phantom_cs: PhantomData<fn() -> (CS, B)>,
+}
+impl<CS, B> Default for RawMcsLock<CS, B> {
+    fn default() -> Self {
+        Self {
+            tail: AtomicPtr::new(null_mut()),
+            owner: AtomicPtr::new(null_mut()),
+            phantom_cs: Default::default(),
+        }
+    }
+}
+unsafe impl<CS, B> RawTryMutex for RawMcsLock<CS, B> {
+    fn try_lock(&self) -> bool {
+        let node = McsNode::new();
+        match self
+            .tail
+            .compare_exchange(null_mut(), node, Ordering::AcqRel, Ordering::Relaxed)
+        {
+            Ok(_) => {
+                self.owner.store(node, Ordering::Relaxed);
+                true
+            }
+            Err(_) => {
+                drop(unsafe { Box::from_raw(node) });
+                false
+            }
+        }
+    }
+
+    unsafe fn unlock(&self) {
+        let node = self.owner.load(Ordering::Relaxed);
+        let mut next = (*node).next.load(Ordering::Acquire);
+        if next.is_null() {
+            if self
+                .tail
+                .compare_exchange(node, null_mut(), Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                drop(Box::from_raw(node));
+                return;
+            }
+            // A waiter has swapped the tail but not linked itself yet
+            while next.is_null() {
+                core::hint::spin_loop();
+                next = (*node).next.load(Ordering::Acquire);
+            }
+        }
+        (*next).locked.store(false, Ordering::Release);
+        drop(Box::from_raw(node));
+    }
+}
+unsafe impl<CS, B> RawMutex for RawMcsLock<CS, B>
+where
+    B: Backoff,
+{
+    fn lock(&self) {
+        let node = McsNode::new();
+        let prev = self.tail.swap(node, Ordering::AcqRel);
+        if !prev.is_null() {
+            unsafe {
+                (*prev).next.store(node, Ordering::Release);
+                let mut backoff = B::default();
+                while (*node).locked.load(Ordering::Acquire) {
+                    backoff.backoff();
+                }
+            }
+        }
+        self.owner.store(node, Ordering::Relaxed);
+    }
+}
+unsafe impl<CS, B> RawTimeoutMutex for RawMcsLock<CS, B>
+where
+    CS: TimeFunctions,
+    B: Backoff,
+{
+    fn lock_timeout(&self, timeout: Duration) -> bool {
+        let end = CS::current_time() + timeout;
+        let mut backoff = B::default();
+        while end > CS::current_time() {
+            if self.try_lock() {
+                return true;
+            }
+            backoff.backoff();
+        }
+        false
+    }
+}
+
+#[derive(Debug)]
+struct McsNode {
+    /// True while waiting for the previous node
+    locked: AtomicBool,
+    next: AtomicPtr<McsNode>,
+}
+impl McsNode {
+    fn new() -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            locked: AtomicBool::new(true),
+            next: AtomicPtr::new(null_mut()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{McsLockStd, Mutex, TimeoutMutex, TryMutex};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mcs_lock_test() {
+        let lock = Arc::new(McsLockStd::new(0usize));
+        let guard = lock.lock();
+        assert!(lock.try_lock().is_none());
+        assert!(lock.lock_timeout(Duration::from_millis(10)).is_none());
+        drop(guard);
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let lock = lock.clone();
+                spawn(move || {
+                    for _ in 0..1000 {
+                        *lock.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(*lock.try_lock().expect("Could not lock"), 8000);
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "alloc")]
+mod adaptive_mutex;
+#[cfg(feature = "alloc")]
+pub use adaptive_mutex::*;
+
 #[cfg(feature = "alloc")]
 mod async_mutex;
 
@@ -7,6 +12,11 @@ pub use async_mutex::*;
 mod atomic_mutex;
 pub use atomic_mutex::*;
 
+#[cfg(feature = "alloc")]
+mod mcs_lock;
+#[cfg(feature = "alloc")]
+pub use mcs_lock::*;
+
 #[cfg(feature = "impl_parking_lot")]
 mod mutex_parking_lot;
 
@@ -21,3 +31,6 @@ pub use spin_lock::*;
 
 #[cfg(feature = "std")]
 mod std_mutex;
+
+mod ticket_lock;
+pub use ticket_lock::*;
@@ -1,5 +1,9 @@
-use crate::mutex::{Mutex, MutexSized, TimeoutMutex, TimeoutMutexSized, TryMutex, TryMutexSized};
-use parking_lot::MutexGuard;
+use crate::mutex::{
+    Mutex, MutexSized, ReentrantMutex, ReentrantMutexSized, TimeoutMutex, TimeoutMutexSized,
+    TimeoutReentrantMutex, TimeoutReentrantMutexSized, TryMutex, TryMutexSized, TryReentrantMutex,
+    TryReentrantMutexSized,
+};
+use parking_lot::{FairMutexGuard, MutexGuard, ReentrantMutexGuard};
 use std::time::Duration;
 
 impl<'a, T: ?Sized> TryMutex<'a> for parking_lot::Mutex<T>
@@ -32,3 +36,65 @@ where
     }
 }
 impl<'a, T> TimeoutMutexSized<'a> for parking_lot::Mutex<T> where T: 'a {}
+
+impl<'a, T: ?Sized> TryMutex<'a> for parking_lot::FairMutex<T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type Guard = FairMutexGuard<'a, T>;
+
+    fn try_lock(&'a self) -> Option<Self::Guard> {
+        self.try_lock()
+    }
+}
+impl<'a, T> TryMutexSized<'a> for parking_lot::FairMutex<T> where T: 'a {}
+impl<'a, T: ?Sized> Mutex<'a> for parking_lot::FairMutex<T>
+where
+    T: 'a,
+{
+    fn lock(&'a self) -> Self::Guard {
+        self.lock()
+    }
+}
+impl<'a, T> MutexSized<'a> for parking_lot::FairMutex<T> where T: 'a {}
+impl<'a, T: ?Sized> TimeoutMutex<'a> for parking_lot::FairMutex<T>
+where
+    T: 'a,
+{
+    fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard> {
+        self.try_lock_for(timeout)
+    }
+}
+impl<'a, T> TimeoutMutexSized<'a> for parking_lot::FairMutex<T> where T: 'a {}
+
+impl<'a, T: ?Sized> TryReentrantMutex<'a> for parking_lot::ReentrantMutex<T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type Guard = ReentrantMutexGuard<'a, T>;
+
+    fn try_lock(&'a self) -> Option<Self::Guard> {
+        self.try_lock()
+    }
+}
+impl<'a, T> TryReentrantMutexSized<'a> for parking_lot::ReentrantMutex<T> where T: 'a {}
+impl<'a, T: ?Sized> ReentrantMutex<'a> for parking_lot::ReentrantMutex<T>
+where
+    T: 'a,
+{
+    fn lock(&'a self) -> Self::Guard {
+        self.lock()
+    }
+}
+impl<'a, T> ReentrantMutexSized<'a> for parking_lot::ReentrantMutex<T> where T: 'a {}
+impl<'a, T: ?Sized> TimeoutReentrantMutex<'a> for parking_lot::ReentrantMutex<T>
+where
+    T: 'a,
+{
+    fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard> {
+        self.try_lock_for(timeout)
+    }
+}
+impl<'a, T> TimeoutReentrantMutexSized<'a> for parking_lot::ReentrantMutex<T> where T: 'a {}
@@ -1,7 +1,11 @@
-use crate::mutex::{CustomMutex, Mutex, RawMutex, RawTimeoutMutex, RawTryMutex, SpinLock};
+use crate::mutex::{
+    CustomMutex, Mutex, RawFairMutex, RawMutex, RawReadoutMutex, RawTimeoutMutex, RawTryMutex,
+    SpinLock,
+};
 use crate::{ThreadFunctions, ThreadParker, ThreadTimeoutParker, TimeFunctions};
 use alloc::collections::VecDeque;
 use alloc::sync::{Arc, Weak};
+use core::fmt::{Debug, Formatter};
 use core::ops::Deref;
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
@@ -18,21 +22,33 @@ pub type RawParkMutexStd = RawParkMutex<crate::StdThreadFunctions>;
 pub type ParkMutex<T, CS> = CustomMutex<T, RawParkMutex<CS>>;
 
 /// The raw portion of [`ParkMutex`].
+///
+/// Unlocking normally wakes the longest waiting thread but lets other threads
+/// barge in before it. If that thread has been parked for longer than the fair
+/// timeout the lock is handed directly to it instead.
+/// [`RawFairMutex::unlock_fair`] always hands the lock off.
 #[derive(Debug)]
 pub struct RawParkMutex<CS>
 where
-    CS: ThreadParker,
+    CS: ThreadParker + TimeFunctions,
 {
     locked: AtomicBool,
+    fair_timeout: Duration,
     inner: SpinLock<RawParkMutexInner<CS>, CS>,
 }
-impl<CS> Default for RawParkMutex<CS>
+impl<CS> RawParkMutex<CS>
 where
-    CS: ThreadParker,
+    CS: ThreadParker + TimeFunctions,
 {
-    fn default() -> Self {
+    /// The fair timeout used by [`Default`]
+    pub const DEFAULT_FAIR_TIMEOUT: Duration = Duration::from_micros(500);
+
+    /// Creates a new mutex that hands the lock off to waiters parked longer
+    /// than `fair_timeout`
+    pub fn with_fair_timeout(fair_timeout: Duration) -> Self {
         Self {
             locked: AtomicBool::new(false),
+            fair_timeout,
             inner: SpinLock::new(RawParkMutexInner {
                 holder: None,
                 parkers: VecDeque::new(),
@@ -40,68 +56,141 @@ where
         }
     }
 }
-unsafe impl<CS> RawTryMutex for RawParkMutex<CS>
+impl<CS> RawParkMutex<CS>
 where
-    CS: ThreadParker + ThreadFunctions,
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
     CS::ThreadId: Clone,
 {
-    fn try_lock(&self) -> bool {
-        !self.locked.swap(true, Ordering::AcqRel)
-    }
-
-    unsafe fn unlock(&self) {
+    /// Unlocks, handing the lock to the first waiter if `force_fair` or it has
+    /// waited longer than the fair timeout.
+    unsafe fn unlock_inner(&self, force_fair: bool) {
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::released(self);
         let mut guard = self.inner.lock();
-        loop {
-            match guard.parkers.pop_front() {
+        while let Some(parker) = guard.parkers.front() {
+            let thread = match parker.thread.upgrade() {
                 None => {
-                    #[cfg(debug_assertions)]
-                    {
-                        assert!(
-                            self.locked.swap(false, Ordering::AcqRel),
-                            "Lock was unlocked while not locked!"
-                        );
-                    }
-                    #[cfg(not(debug_assertions))]
-                    {
-                        self.locked.store(false, Ordering::Release);
-                    }
-                    guard.holder = None;
-                    break;
-                }
-                Some(parker) => {
-                    if let Some(parker) = parker.upgrade() {
-                        guard.holder = Some(parker.deref().clone());
-                        CS::unpark(parker.deref().clone());
-                        break;
-                    }
+                    guard.parkers.pop_front();
+                    continue;
                 }
+                Some(thread) => thread,
             };
+            if force_fair || CS::current_time() - parker.parked_at >= self.fair_timeout {
+                guard.parkers.pop_front();
+                guard.holder = Some(thread.deref().clone());
+            } else {
+                self.release();
+            }
+            CS::unpark(thread.deref().clone());
+            return;
+        }
+        self.release();
+    }
+
+    fn release(&self) {
+        #[cfg(debug_assertions)]
+        {
+            assert!(
+                self.locked.swap(false, Ordering::AcqRel),
+                "Lock was unlocked while not locked!"
+            );
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            self.locked.store(false, Ordering::Release);
         }
     }
 }
+impl<CS> Default for RawParkMutex<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+{
+    fn default() -> Self {
+        Self::with_fair_timeout(Self::DEFAULT_FAIR_TIMEOUT)
+    }
+}
+unsafe impl<CS> RawTryMutex for RawParkMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+{
+    fn try_lock(&self) -> bool {
+        let locked = !self.locked.swap(true, Ordering::AcqRel);
+        #[cfg(feature = "deadlock_detection")]
+        if locked {
+            crate::deadlock::acquired(self);
+        }
+        locked
+    }
+
+    unsafe fn unlock(&self) {
+        self.unlock_inner(false)
+    }
+}
+unsafe impl<CS> RawFairMutex for RawParkMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+{
+    unsafe fn unlock_fair(&self) {
+        self.unlock_inner(true)
+    }
+}
+impl<CS> RawReadoutMutex for RawParkMutex<CS>
+where
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+{
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Acquire)
+    }
+
+    /// Counts the parked threads, excluding any that timed out but were not yet
+    /// removed.
+    fn waiter_count(&self) -> Option<usize> {
+        Some(
+            self.inner
+                .lock()
+                .parkers
+                .iter()
+                .filter(|parker| parker.thread.strong_count() > 0)
+                .count(),
+        )
+    }
+}
 unsafe impl<CS> RawMutex for RawParkMutex<CS>
 where
-    CS: ThreadParker + ThreadFunctions,
+    CS: ThreadParker + ThreadFunctions + TimeFunctions,
     CS::ThreadId: Eq + Clone,
 {
     fn lock(&self) {
         let mut guard = self.inner.lock();
         if !self.try_lock() {
             let self_id = Arc::new(CS::current_thread());
-            guard.parkers.push_back(Arc::downgrade(&self_id));
+            guard.parkers.push_back(Parker {
+                thread: Arc::downgrade(&self_id),
+                parked_at: CS::current_time(),
+            });
+            #[cfg(feature = "deadlock_detection")]
+            crate::deadlock::waiting(self);
             loop {
                 drop(guard);
                 CS::park();
                 guard = self.inner.lock();
-                if let Some(ref holder) = guard.holder {
-                    if holder == self_id.deref() {
-                        // We have been unparked
-                        break;
-                    }
+                if guard.take_handoff(&self_id) || guard.try_barge(self, &self_id) {
+                    break;
                 }
             }
+            #[cfg(feature = "deadlock_detection")]
+            crate::deadlock::acquired(self);
         }
     }
+
+    unsafe fn bump(&self) {
+        self.unlock_fair();
+        self.lock();
+    }
 }
 unsafe impl<CS> RawTimeoutMutex for RawParkMutex<CS>
 where
@@ -110,26 +199,34 @@ where
 {
     fn lock_timeout(&self, timeout: Duration) -> bool {
         let mut guard = self.inner.lock();
-        if !self.try_lock() {
+        if self.try_lock() {
             true
         } else {
-            let end = CS::current_time() + timeout;
+            let start = CS::current_time();
+            let end = start + timeout;
             let self_id = Arc::new(CS::current_thread());
-            guard.parkers.push_back(Arc::downgrade(&self_id));
+            guard.parkers.push_back(Parker {
+                thread: Arc::downgrade(&self_id),
+                parked_at: start,
+            });
+            #[cfg(feature = "deadlock_detection")]
+            crate::deadlock::waiting(self);
             loop {
                 drop(guard);
                 let current_time = CS::current_time();
-                if end < current_time {
+                if current_time < end {
                     CS::park_timeout(end - current_time);
                 }
                 guard = self.inner.lock();
-                if let Some(ref holder) = guard.holder {
-                    if holder == self_id.deref() {
-                        // We have been unparked
-                        return true;
-                    }
+                if guard.take_handoff(&self_id) || guard.try_barge(self, &self_id) {
+                    #[cfg(feature = "deadlock_detection")]
+                    crate::deadlock::acquired(self);
+                    return true;
                 }
                 if CS::current_time() >= end {
+                    #[cfg(feature = "deadlock_detection")]
+                    crate::deadlock::stopped_waiting();
+                    // Our entry dies with `self_id` and is skipped by unlock
                     return false;
                 }
             }
@@ -140,9 +237,54 @@ where
 #[derive(Debug)]
 struct RawParkMutexInner<CS>
 where
-    CS: ThreadParker,
+    CS: ThreadParker + TimeFunctions,
 {
-    /// Only needs to be set when unparking a thread.
+    /// Only needs to be set when handing the lock to a thread.
     holder: Option<CS::ThreadId>,
-    parkers: VecDeque<Weak<CS::ThreadId>>,
+    parkers: VecDeque<Parker<CS>>,
+}
+impl<CS> RawParkMutexInner<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+    CS::ThreadId: Eq,
+{
+    /// Returns true if the lock was handed to `self_id`.
+    fn take_handoff(&mut self, self_id: &Arc<CS::ThreadId>) -> bool {
+        if self.holder.as_ref() == Some(self_id.deref()) {
+            self.holder = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tries to take the lock after being woken without a handoff, leaving the
+    /// queue if successful.
+    fn try_barge(&mut self, mutex: &RawParkMutex<CS>, self_id: &Arc<CS::ThreadId>) -> bool {
+        if mutex.locked.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        let self_ptr = Arc::as_ptr(self_id);
+        self.parkers
+            .retain(|parker| parker.thread.as_ptr() != self_ptr);
+        true
+    }
+}
+
+struct Parker<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+{
+    thread: Weak<CS::ThreadId>,
+    parked_at: CS::InstantType,
+}
+impl<CS> Debug for Parker<CS>
+where
+    CS: ThreadParker + TimeFunctions,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Parker")
+            .field("thread", &self.thread)
+            .finish()
+    }
 }
@@ -1,5 +1,6 @@
+use crate::backoff::{Backoff, DefaultBackoff};
 use crate::mutex::{CustomMutex, RawAtomicMutex, RawMutex, RawTimeoutMutex, RawTryMutex};
-use crate::{ThreadFunctions, TimeFunctions};
+use crate::TimeFunctions;
 use core::marker::PhantomData;
 use core::time::Duration;
 
@@ -8,14 +9,15 @@ use core::time::Duration;
 pub type SpinLockStd<T> = SpinLock<T, crate::StdThreadFunctions>;
 
 /// A lock that spins while being locked. Should only be locked for very short operations.
-pub type SpinLock<T, CS> = CustomMutex<T, RawSpinLock<CS>>;
+/// Waits between attempts with `B`.
+pub type SpinLock<T, CS, B = DefaultBackoff<CS>> = CustomMutex<T, RawSpinLock<CS, B>>;
 /// The raw portion of [`SpinLock`].
 #[derive(Debug)]
-pub struct RawSpinLock<CS> {
+pub struct RawSpinLock<CS, B = DefaultBackoff<CS>> {
     lock: RawAtomicMutex,
-    phantom_cs: PhantomData<fn() -> CS>,
+    phantom_cs: PhantomData<fn() -> (CS, B)>,
 }
-impl<CS> Default for RawSpinLock<CS> {
+impl<CS, B> Default for RawSpinLock<CS, B> {
     fn default() -> Self {
         Self {
             lock: RawAtomicMutex::default(),
@@ -23,7 +25,7 @@ impl<CS> Default for RawSpinLock<CS> {
         }
     }
 }
-unsafe impl<CS> RawTryMutex for RawSpinLock<CS> {
+unsafe impl<CS, B> RawTryMutex for RawSpinLock<CS, B> {
     fn try_lock(&self) -> bool {
         self.lock.try_lock()
     }
@@ -32,27 +34,30 @@ unsafe impl<CS> RawTryMutex for RawSpinLock<CS> {
         self.lock.unlock()
     }
 }
-unsafe impl<CS> RawMutex for RawSpinLock<CS>
+unsafe impl<CS, B> RawMutex for RawSpinLock<CS, B>
 where
-    CS: ThreadFunctions,
+    B: Backoff,
 {
     fn lock(&self) {
+        let mut backoff = B::default();
         while !self.lock.try_lock() {
-            CS::yield_now()
+            backoff.backoff();
         }
     }
 }
-unsafe impl<CS> RawTimeoutMutex for RawSpinLock<CS>
+unsafe impl<CS, B> RawTimeoutMutex for RawSpinLock<CS, B>
 where
-    CS: ThreadFunctions + TimeFunctions,
+    CS: TimeFunctions,
+    B: Backoff,
 {
     fn lock_timeout(&self, timeout: Duration) -> bool {
         let end = CS::current_time() + timeout;
+        let mut backoff = B::default();
         while end > CS::current_time() {
             if self.lock.try_lock() {
                 return true;
             }
-            CS::yield_now();
+            backoff.backoff();
         }
         false
     }
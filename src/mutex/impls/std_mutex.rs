@@ -1,6 +1,6 @@
-use crate::mutex::{Mutex, MutexSized, TryMutex, TryMutexSized};
+use crate::mutex::{FallibleMutex, Mutex, MutexSized, TryFallibleMutex, TryMutex, TryMutexSized};
 use std::sync;
-use std::sync::{MutexGuard, TryLockError};
+use std::sync::{MutexGuard, PoisonError, TryLockError};
 
 impl<'a, T: ?Sized> TryMutex<'a> for sync::Mutex<T>
 where
@@ -30,3 +30,27 @@ where
     }
 }
 impl<'a, T> MutexSized<'a> for sync::Mutex<T> where T: 'a {}
+impl<'a, T: ?Sized> TryFallibleMutex<'a> for sync::Mutex<T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type Guard = MutexGuard<'a, T>;
+    type Error = PoisonError<MutexGuard<'a, T>>;
+
+    fn try_lock_fallible(&'a self) -> Result<Option<Self::Guard>, Self::Error> {
+        match self.try_lock() {
+            Ok(guard) => Ok(Some(guard)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Poisoned(error)) => Err(error),
+        }
+    }
+}
+impl<'a, T: ?Sized> FallibleMutex<'a> for sync::Mutex<T>
+where
+    T: 'a,
+{
+    fn lock_fallible(&'a self) -> Result<Self::Guard, Self::Error> {
+        self.lock()
+    }
+}
@@ -0,0 +1,117 @@
+use crate::backoff::{Backoff, DefaultBackoff};
+use crate::mutex::{CustomMutex, RawMutex, RawTimeoutMutex, RawTryMutex};
+use crate::TimeFunctions;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// A [`TicketLock`] that uses std functions.
+#[cfg(feature = "std")]
+pub type TicketLockStd<T> = TicketLock<T, crate::StdThreadFunctions>;
+
+/// A lock that spins while being locked and hands out the lock in the order it
+/// was requested. Should only be locked for very short operations.
+/// Waits between attempts with `B`.
+pub type TicketLock<T, CS, B = DefaultBackoff<CS>> = CustomMutex<T, RawTicketLock<CS, B>>;
+/// The raw portion of [`TicketLock`].
+///
+/// Timeouts do not take a ticket as an abandoned ticket would block every
+/// later one, so timed out locks are not served in order.
+#[derive(Debug)]
+pub struct RawTicketLock<CS, B = DefaultBackoff<CS>> {
+    next_ticket: AtomicUsize,
+    now_serving: AtomicUsize,
+    phantom_cs: PhantomData<fn() -> (CS, B)>,
+}
+impl<CS, B> Default for RawTicketLock<CS, B> {
+    fn default() -> Self {
+        Self {
+            next_ticket: AtomicUsize::new(0),
+            now_serving: AtomicUsize::new(0),
+            phantom_cs: Default::default(),
+        }
+    }
+}
+unsafe impl<CS, B> RawTryMutex for RawTicketLock<CS, B> {
+    fn try_lock(&self) -> bool {
+        // Only take a ticket if it would be served immediately
+        let serving = self.now_serving.load(Ordering::Acquire);
+        self.next_ticket
+            .compare_exchange(
+                serving,
+                serving.wrapping_add(1),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    unsafe fn unlock(&self) {
+        self.now_serving.fetch_add(1, Ordering::Release);
+    }
+}
+unsafe impl<CS, B> RawMutex for RawTicketLock<CS, B>
+where
+    B: Backoff,
+{
+    fn lock(&self) {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::AcqRel);
+        let mut backoff = B::default();
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            backoff.backoff();
+        }
+    }
+}
+unsafe impl<CS, B> RawTimeoutMutex for RawTicketLock<CS, B>
+where
+    CS: TimeFunctions,
+    B: Backoff,
+{
+    fn lock_timeout(&self, timeout: Duration) -> bool {
+        let end = CS::current_time() + timeout;
+        let mut backoff = B::default();
+        while end > CS::current_time() {
+            if self.try_lock() {
+                return true;
+            }
+            backoff.backoff();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{Mutex, TicketLockStd, TimeoutMutex, TryMutex};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn ticket_lock_test() {
+        let lock = Arc::new(TicketLockStd::new(0usize));
+        let guard = lock.lock();
+        assert!(lock.try_lock().is_none());
+        assert!(lock.lock_timeout(Duration::from_millis(10)).is_none());
+        drop(guard);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                spawn(move || {
+                    for _ in 0..1000 {
+                        *lock.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(*lock.lock(), 4000);
+    }
+}
@@ -0,0 +1,371 @@
+#[cfg(feature = "alloc")]
+use crate::mutex::AsyncMutex;
+use crate::mutex::{Mutex, TimeoutMutex, TryMutex};
+use crate::TimeFunctions;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use async_trait::async_trait;
+use core::ptr;
+use core::time::Duration;
+
+/// A group of mutexes that can be locked together without deadlocking.
+/// Implemented for tuples and arrays of references to mutexes.
+///
+/// Locking blocks on one mutex then tries the rest. If one is not available
+/// all are unlocked and the attempt restarts blocking on the one that failed,
+/// so no lock order is needed between callers.
+///
+/// All functions panic if the same mutex is given more than once as it could
+/// never be locked together with itself.
+pub trait TryLockAll<'a>: Sized {
+    /// The guards for all the mutexes.
+    type Guards;
+
+    /// Tries to lock all the mutexes, returning [`None`] if any are not
+    /// available.
+    fn try_lock_all(self) -> Option<Self::Guards>;
+}
+/// A group of mutexes that can be locked together, blocking.
+pub trait LockAll<'a>: TryLockAll<'a> {
+    /// Locks all the mutexes, blocking until successful.
+    fn lock_all(self) -> Self::Guards;
+}
+/// A group of mutexes that can be locked together with a timeout.
+pub trait TimeoutLockAll<'a>: TryLockAll<'a> {
+    /// Locks all the mutexes, blocking for timeout or until successful.
+    /// Measures the time with `CS`.
+    fn lock_all_timeout<CS>(self, timeout: Duration) -> Option<Self::Guards>
+    where
+        CS: TimeFunctions;
+}
+/// A group of mutexes that can be locked together asynchronously.
+#[cfg(feature = "alloc")]
+#[async_trait]
+pub trait AsyncLockAll<'a>: TryLockAll<'a> {
+    /// Locks all the mutexes asynchronously. Waits on a mutex that was not
+    /// available without holding the others.
+    async fn lock_all_async(self) -> Self::Guards;
+}
+
+/// Panics if any two of `mutexes` have the same address.
+fn assert_distinct(mutexes: &[*const ()]) {
+    for (index, mutex) in mutexes.iter().enumerate() {
+        assert!(
+            !mutexes[index + 1..]
+                .iter()
+                .any(|other| ptr::eq(*mutex, *other)),
+            "The same mutex was given to lock all more than once"
+        );
+    }
+}
+
+/// Locks the mutex at index `$first` with `$lock` then tries the rest.
+/// Evaluates to the guards or the index that could not be locked.
+macro_rules! lock_all_tuple_attempt {
+    ($this:ident, $first:ident, |$mutex:ident| $lock:expr; $($idx:tt $guard:ident),+) => {{
+        $(let mut $guard = None;)+
+        match $first {
+            $($idx => $guard = Some({
+                let $mutex = $this.$idx;
+                $lock
+            }),)+
+            _ => unreachable!(),
+        }
+        let mut failed = None;
+        $(
+            if failed.is_none() && $guard.is_none() {
+                match TryMutex::try_lock($this.$idx) {
+                    Some(guard) => $guard = Some(guard),
+                    None => failed = Some($idx),
+                }
+            }
+        )+
+        match failed {
+            None => Ok(($($guard.unwrap(),)+)),
+            Some(index) => Err(index),
+        }
+    }};
+}
+
+macro_rules! impl_lock_all_tuple {
+    ($($idx:tt $ty:ident $guard:ident),+) => {
+        impl<'a, $($ty),+> TryLockAll<'a> for ($(&'a $ty,)+)
+        where
+            $($ty: TryMutex<'a> + ?Sized,)+
+        {
+            type Guards = ($($ty::Guard,)+);
+
+            fn try_lock_all(self) -> Option<Self::Guards> {
+                assert_distinct(&[$(self.$idx as *const $ty as *const (),)+]);
+                Some(($(TryMutex::try_lock(self.$idx)?,)+))
+            }
+        }
+        impl<'a, $($ty),+> LockAll<'a> for ($(&'a $ty,)+)
+        where
+            $($ty: Mutex<'a> + ?Sized,)+
+        {
+            fn lock_all(self) -> Self::Guards {
+                assert_distinct(&[$(self.$idx as *const $ty as *const (),)+]);
+                let mut first = 0;
+                loop {
+                    match lock_all_tuple_attempt!(self, first, |mutex| Mutex::lock(mutex); $($idx $guard),+) {
+                        Ok(guards) => return guards,
+                        Err(index) => first = index,
+                    }
+                }
+            }
+        }
+        impl<'a, $($ty),+> TimeoutLockAll<'a> for ($(&'a $ty,)+)
+        where
+            $($ty: TimeoutMutex<'a> + ?Sized,)+
+        {
+            fn lock_all_timeout<CS>(self, timeout: Duration) -> Option<Self::Guards>
+            where
+                CS: TimeFunctions,
+            {
+                assert_distinct(&[$(self.$idx as *const $ty as *const (),)+]);
+                let end = CS::current_time() + timeout;
+                let mut first = 0;
+                loop {
+                    let current_time = CS::current_time();
+                    if current_time >= end {
+                        return None;
+                    }
+                    let remaining = end - current_time;
+                    match lock_all_tuple_attempt!(self, first, |mutex| TimeoutMutex::lock_timeout(mutex, remaining)?; $($idx $guard),+) {
+                        Ok(guards) => return Some(guards),
+                        Err(index) => first = index,
+                    }
+                }
+            }
+        }
+        #[cfg(feature = "alloc")]
+        #[async_trait]
+        impl<'a, $($ty),+> AsyncLockAll<'a> for ($(&'a $ty,)+)
+        where
+            $($ty: AsyncMutex<'a> + Sync + ?Sized,)+
+        {
+            async fn lock_all_async(self) -> Self::Guards {
+                assert_distinct(&[$(self.$idx as *const $ty as *const (),)+]);
+                loop {
+                    let index = {
+                        let mut failed = None;
+                        $(
+                            let $guard = match failed {
+                                None => TryMutex::try_lock(self.$idx),
+                                Some(_) => None,
+                            };
+                            if $guard.is_none() && failed.is_none() {
+                                failed = Some($idx);
+                            }
+                        )+
+                        match failed {
+                            None => return ($($guard.unwrap(),)+),
+                            Some(index) => index,
+                        }
+                    };
+                    // Wait for the contended mutex without holding the others
+                    match index {
+                        $($idx => drop(AsyncMutex::lock_async(self.$idx).await),)+
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+    };
+}
+impl_lock_all_tuple!(0 A a, 1 B b);
+impl_lock_all_tuple!(0 A a, 1 B b, 2 C c);
+impl_lock_all_tuple!(0 A a, 1 B b, 2 C c, 3 D d);
+impl_lock_all_tuple!(0 A a, 1 B b, 2 C c, 3 D d, 4 E e);
+impl_lock_all_tuple!(0 A a, 1 B b, 2 C c, 3 D d, 4 E e, 5 F f);
+
+/// Locks the mutex at index `$first` with `$lock` then tries the rest.
+/// Evaluates to the guards or the index that could not be locked.
+macro_rules! lock_all_array_attempt {
+    ($this:ident, $first:ident, |$mutex:ident| $lock:expr) => {{
+        let mut guards = [(); N].map(|_| None);
+        if let Some(&$mutex) = $this.get($first) {
+            guards[$first] = Some($lock);
+        }
+        let mut failed = None;
+        for (index, (guard, mutex)) in guards.iter_mut().zip($this.iter()).enumerate() {
+            if guard.is_none() {
+                match TryMutex::try_lock(*mutex) {
+                    Some(new_guard) => *guard = Some(new_guard),
+                    None => {
+                        failed = Some(index);
+                        break;
+                    }
+                }
+            }
+        }
+        match failed {
+            None => Ok(guards.map(Option::unwrap)),
+            Some(index) => Err(index),
+        }
+    }};
+}
+
+impl<'a, M, const N: usize> TryLockAll<'a> for [&'a M; N]
+where
+    M: TryMutex<'a> + ?Sized,
+{
+    type Guards = [M::Guard; N];
+
+    fn try_lock_all(self) -> Option<Self::Guards> {
+        assert_distinct(&self.map(|mutex| mutex as *const M as *const ()));
+        let mut guards = [(); N].map(|_| None);
+        for (guard, mutex) in guards.iter_mut().zip(self.iter()) {
+            *guard = Some(TryMutex::try_lock(*mutex)?);
+        }
+        Some(guards.map(Option::unwrap))
+    }
+}
+impl<'a, M, const N: usize> LockAll<'a> for [&'a M; N]
+where
+    M: Mutex<'a> + ?Sized,
+{
+    fn lock_all(self) -> Self::Guards {
+        assert_distinct(&self.map(|mutex| mutex as *const M as *const ()));
+        let mut first = 0;
+        loop {
+            match lock_all_array_attempt!(self, first, |mutex| Mutex::lock(mutex)) {
+                Ok(guards) => return guards,
+                Err(index) => first = index,
+            }
+        }
+    }
+}
+impl<'a, M, const N: usize> TimeoutLockAll<'a> for [&'a M; N]
+where
+    M: TimeoutMutex<'a> + ?Sized,
+{
+    fn lock_all_timeout<CS>(self, timeout: Duration) -> Option<Self::Guards>
+    where
+        CS: TimeFunctions,
+    {
+        assert_distinct(&self.map(|mutex| mutex as *const M as *const ()));
+        let end = CS::current_time() + timeout;
+        let mut first = 0;
+        loop {
+            let current_time = CS::current_time();
+            if current_time >= end {
+                return None;
+            }
+            let remaining = end - current_time;
+            match lock_all_array_attempt!(self, first, |mutex| TimeoutMutex::lock_timeout(
+                mutex, remaining
+            )?) {
+                Ok(guards) => return Some(guards),
+                Err(index) => first = index,
+            }
+        }
+    }
+}
+#[cfg(feature = "alloc")]
+#[async_trait]
+impl<'a, M, const N: usize> AsyncLockAll<'a> for [&'a M; N]
+where
+    M: AsyncMutex<'a> + Sync + ?Sized,
+{
+    async fn lock_all_async(self) -> Self::Guards {
+        assert_distinct(&self.map(|mutex| mutex as *const M as *const ()));
+        loop {
+            let index = {
+                let mut guards = [(); N].map(|_| None);
+                let mut failed = None;
+                for (index, (guard, mutex)) in guards.iter_mut().zip(self.iter()).enumerate() {
+                    *guard = TryMutex::try_lock(*mutex);
+                    if guard.is_none() {
+                        failed = Some(index);
+                        break;
+                    }
+                }
+                match failed {
+                    None => return guards.map(Option::unwrap),
+                    Some(index) => index,
+                }
+            };
+            // Wait for the contended mutex without holding the others
+            drop(AsyncMutex::lock_async(self[index]).await);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{LockAll, Mutex, ParkMutexStd, TimeoutLockAll, TryLockAll, TryMutex};
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lock_all_test() {
+        const COUNT: usize = 1000;
+        let mutexes = Arc::new([
+            ParkMutexStd::new(0usize),
+            ParkMutexStd::new(0usize),
+            ParkMutexStd::new(0usize),
+        ]);
+        // Opposite lock orders would deadlock with plain locking
+        let handles: Vec<_> = (0..4)
+            .map(|thread| {
+                let mutexes = mutexes.clone();
+                spawn(move || {
+                    for _ in 0..COUNT {
+                        let (mut a, mut b) = match thread % 2 {
+                            0 => (&mutexes[0], &mutexes[1]).lock_all(),
+                            _ => {
+                                let (b, a) = (&mutexes[1], &mutexes[0]).lock_all();
+                                (a, b)
+                            }
+                        };
+                        *a += 1;
+                        *b += 1;
+                        drop((a, b));
+                        let mut guards = [&mutexes[2], &mutexes[1]].lock_all();
+                        *guards[0] += 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        let guard = mutexes[1].lock();
+        assert!((&mutexes[0], &mutexes[1]).try_lock_all().is_none());
+        assert!([&mutexes[0], &mutexes[1]]
+            .lock_all_timeout::<StdThreadFunctions>(Duration::from_millis(10))
+            .is_none());
+        assert_eq!(*guard, 4 * COUNT);
+        drop(guard);
+        let guards = (&mutexes[0], &mutexes[2])
+            .lock_all_timeout::<StdThreadFunctions>(Duration::from_millis(10))
+            .expect("Could not lock");
+        assert_eq!((*guards.0, *guards.1), (4 * COUNT, 4 * COUNT));
+        assert!(mutexes[0].try_lock().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lock_all_aliased_test() {
+        let mutex = ParkMutexStd::new(0usize);
+        let other = ParkMutexStd::new(0usize);
+        assert!(catch_unwind(AssertUnwindSafe(|| (&mutex, &other, &mutex).lock_all())).is_err());
+        assert!(
+            catch_unwind(AssertUnwindSafe(|| [&mutex, &other, &mutex].try_lock_all())).is_err()
+        );
+        assert!(mutex.try_lock().is_some());
+    }
+}
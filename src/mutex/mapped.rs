@@ -0,0 +1,224 @@
+use crate::mutex::{CustomMutexGuard, RawTryMutex};
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+/// A mutex guard that can be projected to a part of its data, keeping the
+/// mutex locked.
+pub trait MapMutexGuard<'a, U: ?Sized>: Sized + DerefMut {
+    /// The guard that is produced by mapping
+    type MappedGuard: DerefMut<Target = U>;
+
+    /// Makes a new guard for a component of the locked data.
+    fn map(guard: Self, func: impl FnOnce(&mut Self::Target) -> &mut U) -> Self::MappedGuard;
+
+    /// Tries to make a new guard for a component of the locked data, returning
+    /// the original guard if `func` returns [`None`].
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<Self::MappedGuard, Self>;
+}
+
+impl<'a, T, M> CustomMutexGuard<'a, T, M>
+where
+    M: RawTryMutex,
+{
+    /// Makes a [`MappedCustomMutexGuard`] for a component of the locked data.
+    pub fn map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> &mut U,
+    ) -> MappedCustomMutexGuard<'a, U, M> {
+        let data = func(unsafe { &mut *guard.mutex.data.get() });
+        // Only forget the guard once `func` returned so a panic still unlocks
+        let guard = ManuallyDrop::new(guard);
+        MappedCustomMutexGuard::new(&guard.mutex.raw_mutex, data)
+    }
+
+    /// Tries to make a [`MappedCustomMutexGuard`] for a component of the
+    /// locked data, returning the original guard if `func` returns [`None`].
+    pub fn try_map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> Option<&mut U>,
+    ) -> Result<MappedCustomMutexGuard<'a, U, M>, Self> {
+        match func(unsafe { &mut *guard.mutex.data.get() }) {
+            None => Err(guard),
+            Some(data) => {
+                let guard = ManuallyDrop::new(guard);
+                Ok(MappedCustomMutexGuard::new(&guard.mutex.raw_mutex, data))
+            }
+        }
+    }
+}
+impl<'a, T, U, M> MapMutexGuard<'a, U> for CustomMutexGuard<'a, T, M>
+where
+    U: 'a + ?Sized,
+    M: RawTryMutex,
+{
+    type MappedGuard = MappedCustomMutexGuard<'a, U, M>;
+
+    #[inline]
+    fn map(guard: Self, func: impl FnOnce(&mut Self::Target) -> &mut U) -> Self::MappedGuard {
+        Self::map(guard, func)
+    }
+
+    #[inline]
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<Self::MappedGuard, Self> {
+        Self::try_map(guard, func)
+    }
+}
+
+/// A guard for a component of the data in a
+/// [`CustomMutex`](crate::mutex::CustomMutex). Unlocks the mutex on drop.
+#[derive(Debug)]
+pub struct MappedCustomMutexGuard<'a, T: ?Sized, M>
+where
+    M: RawTryMutex,
+{
+    raw_mutex: &'a M,
+    data: *mut T,
+    phantom_data: PhantomData<&'a mut T>,
+}
+impl<'a, T: ?Sized, M> MappedCustomMutexGuard<'a, T, M>
+where
+    M: RawTryMutex,
+{
+    fn new(raw_mutex: &'a M, data: &'a mut T) -> Self {
+        Self {
+            raw_mutex,
+            data,
+            phantom_data: PhantomData,
+        }
+    }
+
+    /// Makes a new [`MappedCustomMutexGuard`] for a component of this guard's
+    /// data.
+    pub fn map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> &mut U,
+    ) -> MappedCustomMutexGuard<'a, U, M> {
+        let data = func(unsafe { &mut *guard.data });
+        let guard = ManuallyDrop::new(guard);
+        MappedCustomMutexGuard::new(guard.raw_mutex, data)
+    }
+
+    /// Tries to make a new [`MappedCustomMutexGuard`] for a component of this
+    /// guard's data, returning the original guard if `func` returns [`None`].
+    pub fn try_map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> Option<&mut U>,
+    ) -> Result<MappedCustomMutexGuard<'a, U, M>, Self> {
+        match func(unsafe { &mut *guard.data }) {
+            None => Err(guard),
+            Some(data) => {
+                let guard = ManuallyDrop::new(guard);
+                Ok(MappedCustomMutexGuard::new(guard.raw_mutex, data))
+            }
+        }
+    }
+}
+impl<'a, T: ?Sized, U, M> MapMutexGuard<'a, U> for MappedCustomMutexGuard<'a, T, M>
+where
+    U: 'a + ?Sized,
+    M: RawTryMutex,
+{
+    type MappedGuard = MappedCustomMutexGuard<'a, U, M>;
+
+    #[inline]
+    fn map(guard: Self, func: impl FnOnce(&mut Self::Target) -> &mut U) -> Self::MappedGuard {
+        Self::map(guard, func)
+    }
+
+    #[inline]
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<Self::MappedGuard, Self> {
+        Self::try_map(guard, func)
+    }
+}
+impl<'a, T: ?Sized, M> Deref for MappedCustomMutexGuard<'a, T, M>
+where
+    M: RawTryMutex,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.data }
+    }
+}
+impl<'a, T: ?Sized, M> DerefMut for MappedCustomMutexGuard<'a, T, M>
+where
+    M: RawTryMutex,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.data }
+    }
+}
+impl<'a, T: ?Sized, M> Drop for MappedCustomMutexGuard<'a, T, M>
+where
+    M: RawTryMutex,
+{
+    fn drop(&mut self) {
+        unsafe { self.raw_mutex.unlock() }
+    }
+}
+unsafe impl<'a, T: ?Sized, M> Send for MappedCustomMutexGuard<'a, T, M>
+where
+    T: Send,
+    M: RawTryMutex + Sync,
+{
+}
+unsafe impl<'a, T: ?Sized, M> Sync for MappedCustomMutexGuard<'a, T, M>
+where
+    T: Sync,
+    M: RawTryMutex + Sync,
+{
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{
+        CustomMutexGuard, MapMutexGuard, MappedCustomMutexGuard, SpinLockStd, TryMutex,
+    };
+    #[cfg(feature = "std")]
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mapped_mutex_guard_test() {
+        let mutex = SpinLockStd::new((0usize, vec![1usize, 2, 3]));
+        let mut guard = CustomMutexGuard::map(mutex.try_lock().expect("Could not lock"), |data| {
+            &mut data.1
+        });
+        guard.push(4);
+        assert!(mutex.try_lock().is_none());
+        let guard = MappedCustomMutexGuard::try_map(guard, |data| data.get_mut(10))
+            .expect_err("Should not map");
+        let mut guard = MapMutexGuard::map(guard, |data: &mut Vec<usize>| &mut data[3]);
+        *guard += 1;
+        drop(guard);
+        assert_eq!(
+            mutex.try_lock().expect("Could not lock").1,
+            vec![1, 2, 3, 5]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn map_panic_test() {
+        let mutex = SpinLockStd::new(0usize);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            CustomMutexGuard::map(
+                mutex.try_lock().expect("Could not lock"),
+                |_| -> &mut usize { panic!("map panicked") },
+            )
+        }));
+        assert!(result.is_err());
+        assert!(mutex.try_lock().is_some());
+    }
+}
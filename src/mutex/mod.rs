@@ -13,17 +13,45 @@ mod async_timeout;
 #[cfg(feature = "alloc")]
 pub use async_timeout::*;
 
+#[cfg(feature = "alloc")]
+mod arc;
+#[cfg(feature = "alloc")]
+pub use arc::*;
+
 mod custom;
 pub use custom::*;
 
+mod custom_reentrant;
+pub use custom_reentrant::*;
+
 #[cfg(feature = "alloc")]
 mod custom_async;
 #[cfg(feature = "alloc")]
 pub use custom_async::*;
 
+mod fair;
+pub use fair::*;
+
+mod fallible;
+pub use fallible::*;
+
+mod lock_all;
+pub use lock_all::*;
+
+mod mapped;
+pub use mapped::*;
+
+mod readout;
+pub use readout::*;
+
+mod reentrant;
+pub use reentrant::*;
+
 mod timeout;
 pub use timeout::*;
 
+mod unlocked;
+
 mod r#try;
 pub use r#try::*;
 
@@ -33,6 +61,16 @@ use core::ops::DerefMut;
 pub unsafe trait RawMutex: RawTryMutex {
     /// Locks the mutex, blocking.
     fn lock(&self);
+
+    /// Unlocks and relocks the mutex, letting a waiting thread go first if the
+    /// mutex supports it.
+    ///
+    /// # Safety
+    /// Same as [`RawTryMutex::unlock`].
+    unsafe fn bump(&self) {
+        self.unlock();
+        self.lock();
+    }
 }
 /// A Generic Mutex trait
 ///
@@ -0,0 +1,66 @@
+use crate::mutex::{CustomMutex, RawTryMutex};
+
+/// A raw mutex whose state can be read.
+pub trait RawReadoutMutex: RawTryMutex {
+    /// Whether the mutex is currently locked.
+    fn is_locked(&self) -> bool;
+
+    /// The number of threads or tasks waiting on the mutex, [`None`] if not
+    /// tracked.
+    fn waiter_count(&self) -> Option<usize> {
+        None
+    }
+}
+/// A mutex whose state can be read.
+pub trait ReadoutMutex {
+    /// Whether the mutex is currently locked.
+    fn is_locked(&self) -> bool;
+
+    /// The number of threads or tasks waiting on the mutex, [`None`] if not
+    /// tracked.
+    fn waiter_count(&self) -> Option<usize>;
+}
+
+impl<T, M> ReadoutMutex for CustomMutex<T, M>
+where
+    M: RawReadoutMutex,
+{
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.raw_mutex.is_locked()
+    }
+
+    #[inline]
+    fn waiter_count(&self) -> Option<usize> {
+        self.raw_mutex.waiter_count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{Mutex, ParkMutexStd, ReadoutMutex, TryMutex};
+    #[cfg(feature = "std")]
+    use std::thread::{scope, sleep};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn readout_test() {
+        let mutex = ParkMutexStd::new(0usize);
+        assert!(!mutex.is_locked());
+        let guard = mutex.try_lock().expect("Could not lock");
+        assert!(mutex.is_locked());
+        assert_eq!(mutex.waiter_count(), Some(0));
+        scope(|scope| {
+            scope.spawn(|| drop(mutex.lock()));
+            while mutex.waiter_count() != Some(1) {
+                sleep(Duration::from_millis(1));
+            }
+            drop(guard);
+        });
+        assert!(!mutex.is_locked());
+        assert_eq!(mutex.waiter_count(), Some(0));
+    }
+}
@@ -0,0 +1,87 @@
+use core::ops::Deref;
+use core::time::Duration;
+
+/// A non-blocking mutex that can be locked multiple times by the same thread.
+/// Guards only give shared access as the same thread can hold several.
+///
+/// ## Implementation
+/// It is recommended to implement [`TryReentrantMutexSized`] if the
+/// implement-ee can be sized.
+pub trait TryReentrantMutex<'a> {
+    /// The item stored in the mutex
+    type Item: ?Sized;
+    /// The guard for the mutex
+    type Guard: Deref<Target = Self::Item>;
+
+    /// Tries to lock the mutex, returning `None` if held by another thread.
+    fn try_lock(&'a self) -> Option<Self::Guard>;
+}
+/// The functions for [`TryReentrantMutex`] that only work for sized types.
+/// Separated to allow [`TryReentrantMutex`] to be a trait object.
+pub trait TryReentrantMutexSized<'a>: Sized + TryReentrantMutex<'a> {
+    /// Runs the function the value in the mutex if available immediately.
+    ///
+    /// ## Implementation
+    /// Should be overwritten by implementors if can be more optimal than
+    /// creating a guard
+    fn try_lock_func<O>(&'a self, func: impl FnOnce(Option<&Self::Item>) -> O) -> O {
+        match self.try_lock() {
+            None => func(None),
+            Some(guard) => func(Some(guard.deref())),
+        }
+    }
+}
+
+/// A mutex that can be locked multiple times by the same thread.
+///
+/// ## Implementation
+/// It is recommended to implement [`ReentrantMutexSized`] if the implement-ee
+/// can be sized.
+pub trait ReentrantMutex<'a>: TryReentrantMutex<'a> {
+    /// Locks the mutex, blocking until successful
+    fn lock(&'a self) -> Self::Guard;
+}
+/// The functions for [`ReentrantMutex`] that only work for sized types.
+/// Separated to allow [`ReentrantMutex`] to be a trait object.
+pub trait ReentrantMutexSized<'a>: ReentrantMutex<'a> + TryReentrantMutexSized<'a> {
+    /// Runs the function on the value in the mutex.
+    ///
+    /// ## Implementation
+    /// Should be overwritten by implementors if can be more optimal than
+    /// creating a guard
+    fn lock_func<O>(&'a self, func: impl FnOnce(&Self::Item) -> O) -> O {
+        func(self.lock().deref())
+    }
+}
+
+/// A reentrant mutex that can timeout for locking
+///
+/// ## Implementation
+/// It is recommended to implement [`TimeoutReentrantMutexSized`] if the
+/// implement-ee can be sized.
+pub trait TimeoutReentrantMutex<'a>: TryReentrantMutex<'a> {
+    /// Locks the mutex blocking for timeout or until locked
+    fn lock_timeout(&'a self, timeout: Duration) -> Option<Self::Guard>;
+}
+/// The functions for [`TimeoutReentrantMutex`] that only work for sized
+/// types. Separated to allow [`TimeoutReentrantMutex`] to be a trait object.
+pub trait TimeoutReentrantMutexSized<'a>:
+    Sized + TimeoutReentrantMutex<'a> + TryReentrantMutexSized<'a>
+{
+    /// Attempts to lock the mutex before timeout has passed and runs func on
+    /// the result
+    ///
+    /// ## Implementation
+    /// Should be overwritten by implementors if can be more optimal than
+    /// creating a guard
+    fn lock_timeout_func<O>(
+        &'a self,
+        timeout: Duration,
+        func: impl FnOnce(Option<&Self::Item>) -> O,
+    ) -> O {
+        match self.lock_timeout(timeout) {
+            None => func(None),
+            Some(guard) => func(Some(guard.deref())),
+        }
+    }
+}
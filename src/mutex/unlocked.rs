@@ -0,0 +1,95 @@
+#[cfg(feature = "alloc")]
+use crate::mutex::RawAsyncMutex;
+use crate::mutex::{CustomMutexGuard, RawMutex};
+use crate::OnDrop;
+#[cfg(feature = "alloc")]
+use core::future::Future;
+#[cfg(feature = "alloc")]
+use core::mem::forget;
+
+impl<'a, T, M> CustomMutexGuard<'a, T, M>
+where
+    M: RawMutex,
+{
+    /// Unlocks the mutex while `func` runs, relocking it before returning.
+    /// The mutex is relocked even if `func` panics.
+    pub fn unlocked<O>(guard: &mut Self, func: impl FnOnce() -> O) -> O {
+        let raw_mutex = &guard.mutex.raw_mutex;
+        unsafe { raw_mutex.unlock() }
+        let _relock = OnDrop(|| raw_mutex.lock());
+        func()
+    }
+
+    /// Unlocks and relocks the mutex with [`RawMutex::bump`], letting a
+    /// waiting thread go first if the mutex supports it.
+    pub fn bump(guard: &mut Self) {
+        unsafe { guard.mutex.raw_mutex.bump() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a, T, M> CustomMutexGuard<'a, T, M>
+where
+    M: RawAsyncMutex,
+{
+    /// Unlocks the mutex while `future` runs, relocking it asynchronously
+    /// and returning the guard with the output. Takes the guard so nothing
+    /// is held or relocked if `future` panics or this is dropped early.
+    pub async fn unlocked_async<O>(guard: Self, future: impl Future<Output = O>) -> (Self, O) {
+        let mutex = guard.mutex;
+        forget(guard);
+        unsafe { mutex.raw_mutex.unlock() }
+        let out = future.await;
+        mutex.raw_mutex.lock_async().await;
+        (CustomMutexGuard { mutex }, out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::mutex::{CustomMutexGuard, FullAsyncMutex, Mutex, ParkMutexStd, TryMutex};
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use std::future::{pending, Future};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::task::{Context, Waker};
+    #[cfg(feature = "std")]
+    use std::thread::{sleep, spawn};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn unlocked_test() {
+        let mutex = Arc::new(ParkMutexStd::new(0usize));
+        let mut guard = mutex.lock();
+        CustomMutexGuard::unlocked(&mut guard, || *mutex.try_lock().expect("Not unlocked") += 1);
+        assert!(mutex.try_lock().is_none());
+        let mutex_clone = mutex.clone();
+        let handle = spawn(move || *mutex_clone.lock() += 1);
+        sleep(Duration::from_millis(50));
+        // The waiting thread goes first
+        CustomMutexGuard::bump(&mut guard);
+        assert_eq!(*guard, 2);
+        drop(guard);
+        handle.join().expect("Could not join");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn unlocked_async_cancel_test() {
+        let mutex = FullAsyncMutex::<_, ParkQueueStd<_>>::new(0usize);
+        let guard = mutex.try_lock().expect("Could not lock");
+        let mut future = Box::pin(CustomMutexGuard::unlocked_async(guard, pending::<()>()));
+        assert!(future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+            .is_pending());
+        assert!(mutex.try_lock().is_some());
+        drop(future);
+        assert!(mutex.try_lock().is_some());
+    }
+}
@@ -0,0 +1,453 @@
+//! Opt-in poisoning for custom locks.
+
+use crate::mutex::{
+    CustomMutex, CustomMutexGuard, FallibleMutex, RawAsyncMutex, RawMutex, RawTimeoutMutex,
+    RawTryMutex, TimeoutFallibleMutex, TryFallibleMutex,
+};
+use crate::rw_lock::{
+    CustomReadGuard, CustomRwLock, CustomWriteGuard, FallibleRwLock, RawAsyncRwLock,
+    RawDowngradeRwLock, RawRwLock, RawTimeoutRwLock, RawTryRwLock, RawTryUpgradeRwLock,
+    RawUpgradeRwLock, TimeoutFallibleRwLock, TryFallibleRwLock,
+};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LockResult, PoisonError};
+use std::thread::panicking;
+use std::time::Duration;
+
+/// A [`CustomMutex`] that is poisoned if a guard is dropped while panicking.
+pub type PoisonMutex<T, M> = CustomMutex<T, Poison<M>>;
+
+/// A [`CustomRwLock`] that is poisoned if a write guard is dropped while
+/// panicking.
+pub type PoisonRwLock<T, R> = CustomRwLock<T, Poison<R>>;
+
+/// Wraps a raw mutex or rw lock, marking it poisoned if it is unlocked while
+/// the thread is panicking and was not already panicking when it locked. Only
+/// write locks are poisoned for rw locks.
+#[derive(Debug, Default)]
+pub struct Poison<R> {
+    raw: R,
+    poisoned: AtomicBool,
+    /// Whether the current holder was panicking when it locked, only accessed
+    /// while exclusively locked
+    locked_while_panicking: AtomicBool,
+}
+impl<R> Poison<R> {
+    /// Wraps a raw lock
+    pub const fn new(raw: R) -> Self {
+        Self {
+            raw,
+            poisoned: AtomicBool::new(false),
+            locked_while_panicking: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether a lock was released while panicking
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Clears the poisoned state
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release)
+    }
+
+    fn exclusive_locked(&self, locked: bool) -> bool {
+        if locked {
+            self.locked_while_panicking
+                .store(panicking(), Ordering::Relaxed);
+        }
+        locked
+    }
+
+    fn poison_if_panicking(&self) {
+        if panicking() && !self.locked_while_panicking.load(Ordering::Relaxed) {
+            self.poisoned.store(true, Ordering::Release);
+        }
+    }
+
+    fn check<G>(&self, guard: G) -> LockResult<G> {
+        match self.is_poisoned() {
+            true => Err(PoisonError::new(guard)),
+            false => Ok(guard),
+        }
+    }
+
+    fn check_option<G>(&self, guard: Option<G>) -> Result<Option<G>, PoisonError<G>> {
+        match guard {
+            None => Ok(None),
+            Some(guard) => self.check(guard).map(Some),
+        }
+    }
+}
+unsafe impl<M> RawTryMutex for Poison<M>
+where
+    M: RawTryMutex,
+{
+    #[inline]
+    fn try_lock(&self) -> bool {
+        self.exclusive_locked(self.raw.try_lock())
+    }
+
+    unsafe fn unlock(&self) {
+        self.poison_if_panicking();
+        self.raw.unlock()
+    }
+}
+unsafe impl<M> RawMutex for Poison<M>
+where
+    M: RawMutex,
+{
+    #[inline]
+    fn lock(&self) {
+        self.raw.lock();
+        self.exclusive_locked(true);
+    }
+}
+unsafe impl<M> RawTimeoutMutex for Poison<M>
+where
+    M: RawTimeoutMutex,
+{
+    #[inline]
+    fn lock_timeout(&self, timeout: Duration) -> bool {
+        self.exclusive_locked(self.raw.lock_timeout(timeout))
+    }
+}
+#[async_trait]
+unsafe impl<M> RawAsyncMutex for Poison<M>
+where
+    M: RawAsyncMutex + Sync,
+{
+    async fn lock_async(&self) {
+        self.raw.lock_async().await;
+        self.exclusive_locked(true);
+    }
+}
+unsafe impl<R> RawTryRwLock for Poison<R>
+where
+    R: RawTryRwLock,
+{
+    #[inline]
+    fn try_add_reader(&self) -> bool {
+        self.raw.try_add_reader()
+    }
+
+    #[inline]
+    fn try_add_writer(&self) -> bool {
+        self.exclusive_locked(self.raw.try_add_writer())
+    }
+
+    #[inline]
+    unsafe fn remove_reader(&self) {
+        self.raw.remove_reader()
+    }
+
+    unsafe fn remove_writer(&self) {
+        self.poison_if_panicking();
+        self.raw.remove_writer()
+    }
+}
+unsafe impl<R> RawRwLock for Poison<R>
+where
+    R: RawRwLock,
+{
+    #[inline]
+    fn add_reader(&self) {
+        self.raw.add_reader()
+    }
+
+    #[inline]
+    fn add_writer(&self) {
+        self.raw.add_writer();
+        self.exclusive_locked(true);
+    }
+}
+unsafe impl<R> RawTimeoutRwLock for Poison<R>
+where
+    R: RawTimeoutRwLock,
+{
+    #[inline]
+    fn add_reader_timeout(&self, timeout: Duration) -> bool {
+        self.raw.add_reader_timeout(timeout)
+    }
+
+    #[inline]
+    fn add_writer_timeout(&self, timeout: Duration) -> bool {
+        self.exclusive_locked(self.raw.add_writer_timeout(timeout))
+    }
+}
+#[async_trait]
+unsafe impl<R> RawAsyncRwLock for Poison<R>
+where
+    R: RawAsyncRwLock + Sync,
+{
+    async fn add_reader_async(&self) {
+        self.raw.add_reader_async().await
+    }
+
+    async fn add_writer_async(&self) {
+        self.raw.add_writer_async().await;
+        self.exclusive_locked(true);
+    }
+}
+unsafe impl<R> RawTryUpgradeRwLock for Poison<R>
+where
+    R: RawTryUpgradeRwLock,
+{
+    #[inline]
+    unsafe fn try_upgrade(&self) -> bool {
+        self.exclusive_locked(self.raw.try_upgrade())
+    }
+}
+unsafe impl<R> RawUpgradeRwLock for Poison<R>
+where
+    R: RawUpgradeRwLock,
+{
+    #[inline]
+    unsafe fn upgrade(&self) {
+        self.raw.upgrade();
+        self.exclusive_locked(true);
+    }
+}
+unsafe impl<R> RawDowngradeRwLock for Poison<R>
+where
+    R: RawDowngradeRwLock,
+{
+    unsafe fn downgrade(&self) {
+        self.poison_if_panicking();
+        self.raw.downgrade()
+    }
+}
+
+impl<T, M> CustomMutex<T, Poison<M>> {
+    /// Whether a guard was dropped while panicking
+    pub fn is_poisoned(&self) -> bool {
+        self.raw_mutex.is_poisoned()
+    }
+
+    /// Clears the poisoned state
+    pub fn clear_poison(&self) {
+        self.raw_mutex.clear_poison()
+    }
+
+    /// Consumes the mutex returning the data, or an error containing it if
+    /// poisoned.
+    pub fn into_inner(self) -> LockResult<T> {
+        let poisoned = self.is_poisoned();
+        let data = self.data.into_inner();
+        match poisoned {
+            true => Err(PoisonError::new(data)),
+            false => Ok(data),
+        }
+    }
+}
+impl<'a, T, M> TryFallibleMutex<'a> for CustomMutex<T, Poison<M>>
+where
+    T: 'a,
+    M: RawTryMutex + 'a,
+{
+    type Item = T;
+    type Guard = CustomMutexGuard<'a, T, Poison<M>>;
+    type Error = PoisonError<Self::Guard>;
+
+    fn try_lock_fallible(&'a self) -> Result<Option<Self::Guard>, Self::Error> {
+        let guard = match self.raw_mutex.try_lock() {
+            true => Some(CustomMutexGuard { mutex: self }),
+            false => None,
+        };
+        self.raw_mutex.check_option(guard)
+    }
+}
+impl<'a, T, M> FallibleMutex<'a> for CustomMutex<T, Poison<M>>
+where
+    T: 'a,
+    M: RawMutex + 'a,
+{
+    fn lock_fallible(&'a self) -> Result<Self::Guard, Self::Error> {
+        self.raw_mutex.lock();
+        self.raw_mutex.check(CustomMutexGuard { mutex: self })
+    }
+}
+impl<'a, T, M> TimeoutFallibleMutex<'a> for CustomMutex<T, Poison<M>>
+where
+    T: 'a,
+    M: RawTimeoutMutex + 'a,
+{
+    fn lock_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::Guard>, Self::Error> {
+        let guard = match self.raw_mutex.lock_timeout(timeout) {
+            true => Some(CustomMutexGuard { mutex: self }),
+            false => None,
+        };
+        self.raw_mutex.check_option(guard)
+    }
+}
+
+impl<T, R> CustomRwLock<T, Poison<R>> {
+    /// Whether a write guard was dropped while panicking
+    pub fn is_poisoned(&self) -> bool {
+        self.raw_lock.is_poisoned()
+    }
+
+    /// Clears the poisoned state
+    pub fn clear_poison(&self) {
+        self.raw_lock.clear_poison()
+    }
+
+    /// Consumes the lock returning the data, or an error containing it if
+    /// poisoned.
+    pub fn into_inner(self) -> LockResult<T> {
+        let poisoned = self.is_poisoned();
+        let data = self.data.into_inner();
+        match poisoned {
+            true => Err(PoisonError::new(data)),
+            false => Ok(data),
+        }
+    }
+}
+impl<'a, T, R> TryFallibleRwLock<'a> for CustomRwLock<T, Poison<R>>
+where
+    T: 'a,
+    R: RawTryRwLock + 'a,
+{
+    type Item = T;
+    type ReadGuard = CustomReadGuard<'a, T, Poison<R>>;
+    type WriteGuard = CustomWriteGuard<'a, T, Poison<R>>;
+    type ReadError = PoisonError<Self::ReadGuard>;
+    type WriteError = PoisonError<Self::WriteGuard>;
+
+    fn try_read_fallible(&'a self) -> Result<Option<Self::ReadGuard>, Self::ReadError> {
+        let guard = match self.raw_lock.try_add_reader() {
+            true => Some(CustomReadGuard { lock: self }),
+            false => None,
+        };
+        self.raw_lock.check_option(guard)
+    }
+
+    fn try_write_fallible(&'a self) -> Result<Option<Self::WriteGuard>, Self::WriteError> {
+        let guard = match self.raw_lock.try_add_writer() {
+            true => Some(CustomWriteGuard { lock: self }),
+            false => None,
+        };
+        self.raw_lock.check_option(guard)
+    }
+}
+impl<'a, T, R> FallibleRwLock<'a> for CustomRwLock<T, Poison<R>>
+where
+    T: 'a,
+    R: RawRwLock + 'a,
+{
+    fn read_fallible(&'a self) -> Result<Self::ReadGuard, Self::ReadError> {
+        self.raw_lock.add_reader();
+        self.raw_lock.check(CustomReadGuard { lock: self })
+    }
+
+    fn write_fallible(&'a self) -> Result<Self::WriteGuard, Self::WriteError> {
+        self.raw_lock.add_writer();
+        self.raw_lock.check(CustomWriteGuard { lock: self })
+    }
+}
+impl<'a, T, R> TimeoutFallibleRwLock<'a> for CustomRwLock<T, Poison<R>>
+where
+    T: 'a,
+    R: RawTimeoutRwLock + 'a,
+{
+    fn read_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadGuard>, Self::ReadError> {
+        let guard = match self.raw_lock.add_reader_timeout(timeout) {
+            true => Some(CustomReadGuard { lock: self }),
+            false => None,
+        };
+        self.raw_lock.check_option(guard)
+    }
+
+    fn write_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteGuard>, Self::WriteError> {
+        let guard = match self.raw_lock.add_writer_timeout(timeout) {
+            true => Some(CustomWriteGuard { lock: self }),
+            false => None,
+        };
+        self.raw_lock.check_option(guard)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::mutex::{FallibleMutex, Mutex, RawSpinLock};
+    use crate::poison::{PoisonMutex, PoisonRwLock};
+    use crate::rw_lock::{FallibleRwLock, RawSpinRwLock, RwLock, TryFallibleRwLock};
+    use crate::StdThreadFunctions;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::Arc;
+    use std::thread::spawn;
+
+    #[test]
+    fn poison_mutex_test() {
+        let mutex = Arc::new(PoisonMutex::<_, RawSpinLock<StdThreadFunctions>>::new(0));
+        *mutex.lock_fallible().expect("Should not be poisoned") += 1;
+        let mutex_clone = mutex.clone();
+        assert!(spawn(move || {
+            let _guard = mutex_clone.lock();
+            panic!("Poisoning");
+        })
+        .join()
+        .is_err());
+        assert!(mutex.is_poisoned());
+        let guard = mutex.lock_fallible().expect_err("Should be poisoned");
+        assert_eq!(*guard.into_inner(), 1);
+        mutex.clear_poison();
+        *mutex.lock_fallible().expect("Should not be poisoned") += 1;
+        let mutex = Arc::try_unwrap(mutex).expect("Should be unique");
+        assert_eq!(mutex.into_inner().expect("Should not be poisoned"), 2);
+    }
+
+    #[test]
+    fn poison_locked_while_panicking_test() {
+        struct LockOnDrop<'a>(&'a PoisonMutex<usize, RawSpinLock<StdThreadFunctions>>);
+        impl Drop for LockOnDrop<'_> {
+            fn drop(&mut self) {
+                *self.0.lock() += 1;
+            }
+        }
+
+        let mutex = PoisonMutex::<_, RawSpinLock<StdThreadFunctions>>::new(0);
+        assert!(catch_unwind(AssertUnwindSafe(|| {
+            let _lock_on_drop = LockOnDrop(&mutex);
+            panic!("Not poisoning");
+        }))
+        .is_err());
+        assert!(!mutex.is_poisoned());
+        assert_eq!(*mutex.lock_fallible().expect("Should not be poisoned"), 1);
+    }
+
+    #[test]
+    fn poison_rw_lock_test() {
+        let lock = Arc::new(PoisonRwLock::<_, RawSpinRwLock<StdThreadFunctions>>::new(0));
+        let lock_clone = lock.clone();
+        assert!(spawn(move || {
+            let _guard = lock_clone.read();
+            panic!("Not poisoning");
+        })
+        .join()
+        .is_err());
+        assert!(!lock.is_poisoned());
+        let lock_clone = lock.clone();
+        assert!(spawn(move || {
+            let _guard = lock_clone.write();
+            panic!("Poisoning");
+        })
+        .join()
+        .is_err());
+        assert!(lock.read_fallible().is_err());
+        assert!(lock.try_write_fallible().is_err());
+        lock.clear_poison();
+        assert_eq!(*lock.write_fallible().expect("Should not be poisoned"), 0);
+    }
+}
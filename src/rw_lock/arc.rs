@@ -0,0 +1,203 @@
+use crate::rw_lock::{CustomRwLock, RawAsyncRwLock, RawRwLock, RawTryRwLock};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use async_trait::async_trait;
+use core::ops::{Deref, DerefMut};
+
+/// A rw lock in an [`Arc`] that can give owned guards, non-blocking.
+pub trait TryArcRwLock {
+    /// The item stored by this lock
+    type Item: ?Sized;
+    /// The owned guard for reading from this lock
+    type ArcReadGuard: Deref<Target = Self::Item>;
+    /// The owned guard for writing to this lock
+    type ArcWriteGuard: DerefMut<Target = Self::Item>;
+
+    /// Tries to read from the lock, returning [`None`] if cannot immediately
+    fn try_read_arc(self: &Arc<Self>) -> Option<Self::ArcReadGuard>;
+
+    /// Tries to write to the lock, returning [`None`] if cannot immediately
+    fn try_write_arc(self: &Arc<Self>) -> Option<Self::ArcWriteGuard>;
+}
+/// A rw lock in an [`Arc`] that can give owned guards, blocking.
+pub trait ArcRwLock: TryArcRwLock {
+    /// Reads from the lock, blocking until able.
+    fn read_arc(self: &Arc<Self>) -> Self::ArcReadGuard;
+
+    /// Writes to the lock, blocking until able.
+    fn write_arc(self: &Arc<Self>) -> Self::ArcWriteGuard;
+}
+/// A rw lock in an [`Arc`] that can give owned guards asynchronously.
+#[async_trait]
+pub trait AsyncArcRwLock: TryArcRwLock {
+    /// Reads the lock asynchronously, giving a future that will contain the
+    /// read lock
+    async fn read_async_arc(self: &Arc<Self>) -> Self::ArcReadGuard;
+
+    /// Writes to the lock asynchronously, giving a future that will contain the
+    /// write lock
+    async fn write_async_arc(self: &Arc<Self>) -> Self::ArcWriteGuard;
+}
+
+impl<T, R> TryArcRwLock for CustomRwLock<T, R>
+where
+    R: RawTryRwLock,
+{
+    type Item = T;
+    type ArcReadGuard = ArcCustomReadGuard<T, R>;
+    type ArcWriteGuard = ArcCustomWriteGuard<T, R>;
+
+    fn try_read_arc(self: &Arc<Self>) -> Option<Self::ArcReadGuard> {
+        match self.raw_lock.try_add_reader() {
+            true => Some(ArcCustomReadGuard { lock: self.clone() }),
+            false => None,
+        }
+    }
+
+    fn try_write_arc(self: &Arc<Self>) -> Option<Self::ArcWriteGuard> {
+        match self.raw_lock.try_add_writer() {
+            true => Some(ArcCustomWriteGuard { lock: self.clone() }),
+            false => None,
+        }
+    }
+}
+impl<T, R> ArcRwLock for CustomRwLock<T, R>
+where
+    R: RawRwLock,
+{
+    fn read_arc(self: &Arc<Self>) -> Self::ArcReadGuard {
+        self.raw_lock.add_reader();
+        ArcCustomReadGuard { lock: self.clone() }
+    }
+
+    fn write_arc(self: &Arc<Self>) -> Self::ArcWriteGuard {
+        self.raw_lock.add_writer();
+        ArcCustomWriteGuard { lock: self.clone() }
+    }
+}
+#[async_trait]
+impl<T, R> AsyncArcRwLock for CustomRwLock<T, R>
+where
+    T: Send + Sync,
+    R: RawAsyncRwLock + Send + Sync,
+{
+    async fn read_async_arc(self: &Arc<Self>) -> Self::ArcReadGuard {
+        self.raw_lock.add_reader_async().await;
+        ArcCustomReadGuard { lock: self.clone() }
+    }
+
+    async fn write_async_arc(self: &Arc<Self>) -> Self::ArcWriteGuard {
+        self.raw_lock.add_writer_async().await;
+        ArcCustomWriteGuard { lock: self.clone() }
+    }
+}
+
+/// An owned read guard for a [`CustomRwLock`] in an [`Arc`]
+#[derive(Debug)]
+pub struct ArcCustomReadGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    lock: Arc<CustomRwLock<T, R>>,
+}
+impl<T, R> ArcCustomReadGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    /// Gets the lock this guard is for
+    pub fn lock(guard: &Self) -> &Arc<CustomRwLock<T, R>> {
+        &guard.lock
+    }
+}
+impl<T, R> Deref for ArcCustomReadGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+impl<T, R> Drop for ArcCustomReadGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    fn drop(&mut self) {
+        unsafe { self.lock.raw_lock.remove_reader() }
+    }
+}
+
+/// An owned write guard for a [`CustomRwLock`] in an [`Arc`]
+#[derive(Debug)]
+pub struct ArcCustomWriteGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    lock: Arc<CustomRwLock<T, R>>,
+}
+impl<T, R> ArcCustomWriteGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    /// Gets the lock this guard is for
+    pub fn lock(guard: &Self) -> &Arc<CustomRwLock<T, R>> {
+        &guard.lock
+    }
+}
+impl<T, R> Deref for ArcCustomWriteGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+impl<T, R> DerefMut for ArcCustomWriteGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+impl<T, R> Drop for ArcCustomWriteGuard<T, R>
+where
+    R: RawTryRwLock,
+{
+    fn drop(&mut self) {
+        unsafe { self.lock.raw_lock.remove_writer() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{ArcRwLock, ParkRwLockStd, TryArcRwLock};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn arc_rw_lock_test() {
+        let lock = Arc::new(ParkRwLockStd::<usize>::new(0));
+        let mut write_guard = lock.write_arc();
+        assert!(lock.try_read_arc().is_none());
+        let reader = {
+            let lock = lock.clone();
+            spawn(move || *lock.read_arc())
+        };
+        spawn(move || *write_guard += 1)
+            .join()
+            .expect("Could not join");
+        assert_eq!(reader.join().expect("Could not join"), 1);
+        let read_guard = lock.read_arc();
+        assert!(lock.try_read_arc().is_some());
+        assert!(lock.try_write_arc().is_none());
+        drop(read_guard);
+    }
+}
@@ -1,12 +1,12 @@
-use crate::rw_lock::RawTryRwLock;
+use crate::rw_lock::{RawTryRwLock, RawTryUpgradableRwLock};
 use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
 
 /// A custom rw lock that can be built from any [`RawTryRwLock`] variant
 #[derive(Debug)]
 pub struct CustomRwLock<T, R> {
-    pub(super) data: UnsafeCell<T>,
-    pub(super) raw_lock: R,
+    pub(crate) data: UnsafeCell<T>,
+    pub(crate) raw_lock: R,
 }
 impl<T, R> CustomRwLock<T, R> {
     /// Creates a lock from a [`RawTryRwLock`] variant
@@ -39,7 +39,7 @@ pub struct CustomReadGuard<'a, T, R>
 where
     R: RawTryRwLock,
 {
-    pub(super) lock: &'a CustomRwLock<T, R>,
+    pub(crate) lock: &'a CustomRwLock<T, R>,
 }
 impl<'a, T, R> Deref for CustomReadGuard<'a, T, R>
 where
@@ -66,7 +66,7 @@ pub struct CustomWriteGuard<'a, T, R>
 where
     R: RawTryRwLock,
 {
-    pub(super) lock: &'a CustomRwLock<T, R>,
+    pub(crate) lock: &'a CustomRwLock<T, R>,
 }
 impl<'a, T, R> Deref for CustomWriteGuard<'a, T, R>
 where
@@ -94,3 +94,30 @@ where
         unsafe { self.lock.raw_lock.remove_writer() }
     }
 }
+
+/// The upgradable read guard for [`CustomRwLock`]
+#[derive(Debug)]
+pub struct CustomUpgradableReadGuard<'a, T, R>
+where
+    R: RawTryUpgradableRwLock,
+{
+    pub(crate) lock: &'a CustomRwLock<T, R>,
+}
+impl<'a, T, R> Deref for CustomUpgradableReadGuard<'a, T, R>
+where
+    R: RawTryUpgradableRwLock,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+impl<'a, T, R> Drop for CustomUpgradableReadGuard<'a, T, R>
+where
+    R: RawTryUpgradableRwLock,
+{
+    fn drop(&mut self) {
+        unsafe { self.lock.raw_lock.remove_upgradable() }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::blocking_bridge::{BlockingBridge, BridgeMessage, MessageSender};
+use crate::queue::Queue;
+use crate::rw_lock::*;
+use crate::TryThreadSpawner;
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use async_trait::async_trait;
+use core::any::Any;
+use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
+
+/// A Custom rw lock with async operations based on a [`RawRwLock`].
+pub type CustomAsyncRwLock<T, R, Q> = CustomRwLock<T, RawCustomAsyncRwLock<R, Q>>;
+
+/// A converter for turning a [`RawRwLock`] into a [`RawAsyncRwLock`]. Runs all
+/// operations on own task, or on a shared [`BlockingBridge`] if created with
+/// [`RawCustomAsyncRwLock::from_bridge`].
+///
+/// An [`upgrade_async`](RawAsyncUpgradeRwLock::upgrade_async) future must be
+/// run to completion, as the upgrade continues on the converter even if the
+/// future is dropped.
+#[derive(Debug)]
+pub struct RawCustomAsyncRwLock<R, Q> {
+    inner: Arc<RawCustomAsyncRwLockInner<R, Q>>,
+}
+impl<R, Q> RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + Send + Sync,
+    Q: 'static + Queue<Item = RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    /// Creates a new [`RawCustomAsyncRwLock`] from a [`RawRwLock`] and a
+    /// message queue.
+    pub fn new<TS>(
+        raw_lock: R,
+        message_queue: Q,
+    ) -> Result<(Self, TS::ThreadHandle), TS::SpawnError>
+    where
+        TS: TryThreadSpawner<()>,
+    {
+        let out = Self {
+            inner: Arc::new(RawCustomAsyncRwLockInner {
+                raw_lock,
+                message_queue,
+            }),
+        };
+        let raw_lock_clone = Arc::downgrade(&out.inner);
+        Ok((
+            out,
+            TS::try_spawn(move || Self::thread_function(raw_lock_clone))?,
+        ))
+    }
+
+    fn thread_function(inner: Weak<RawCustomAsyncRwLockInner<R, Q>>) {
+        while let Some(inner) = inner.upgrade() {
+            let message = inner.message_queue.pop();
+            inner.handle_message(message);
+        }
+    }
+}
+impl<R, Q> RawCustomAsyncRwLock<R, BlockingBridge<Q>>
+where
+    R: 'static + RawRwLock + Send + Sync,
+    Q: 'static + Queue<Item = BridgeMessage> + Send + Sync,
+{
+    /// Creates a new [`RawCustomAsyncRwLock`] from a [`RawRwLock`] that sends
+    /// its operations to a shared [`BlockingBridge`] rather than its own thread.
+    pub fn from_bridge(raw_lock: R, bridge: BlockingBridge<Q>) -> Self {
+        Self {
+            inner: Arc::new(RawCustomAsyncRwLockInner {
+                raw_lock,
+                message_queue: bridge,
+            }),
+        }
+    }
+}
+impl<R, Q> RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    fn send(&self, message: RawCustomAsyncRwLockMessage) {
+        let inner = self.inner.clone();
+        self.inner
+            .message_queue
+            .send(message, move |message| inner.handle_message(message));
+    }
+}
+unsafe impl<R, Q> RawTryRwLock for RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    #[inline]
+    fn try_add_reader(&self) -> bool {
+        self.inner.raw_lock.try_add_reader()
+    }
+
+    #[inline]
+    fn try_add_writer(&self) -> bool {
+        self.inner.raw_lock.try_add_writer()
+    }
+
+    #[inline]
+    unsafe fn remove_reader(&self) {
+        self.inner.raw_lock.remove_reader()
+    }
+
+    #[inline]
+    unsafe fn remove_writer(&self) {
+        self.inner.raw_lock.remove_writer()
+    }
+}
+unsafe impl<R, Q> RawRwLock for RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    #[inline]
+    fn add_reader(&self) {
+        self.inner.raw_lock.add_reader()
+    }
+
+    #[inline]
+    fn add_writer(&self) {
+        self.inner.raw_lock.add_writer()
+    }
+}
+#[async_trait]
+unsafe impl<R, Q> RawAsyncRwLock for RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    async fn add_reader_async(&self) {
+        let future = CompleteFuture::new();
+        self.send(RawCustomAsyncRwLockMessage::AddReader(future.get_handle()));
+        future.await;
+    }
+
+    async fn add_writer_async(&self) {
+        let future = CompleteFuture::new();
+        self.send(RawCustomAsyncRwLockMessage::AddWriter(future.get_handle()));
+        future.await;
+    }
+}
+unsafe impl<R, Q> RawTryUpgradeRwLock for RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + RawUpgradeRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    #[inline]
+    unsafe fn try_upgrade(&self) -> bool {
+        self.inner.raw_lock.try_upgrade()
+    }
+}
+unsafe impl<R, Q> RawUpgradeRwLock for RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + RawUpgradeRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    #[inline]
+    unsafe fn upgrade(&self) {
+        self.inner.raw_lock.upgrade()
+    }
+}
+#[async_trait]
+unsafe impl<R, Q> RawAsyncUpgradeRwLock for RawCustomAsyncRwLock<R, Q>
+where
+    R: 'static + RawRwLock + RawUpgradeRwLock + Send + Sync,
+    Q: 'static + MessageSender<RawCustomAsyncRwLockMessage> + Send + Sync,
+{
+    async unsafe fn upgrade_async(&self) {
+        if self.inner.raw_lock.try_upgrade() {
+            return;
+        }
+        let future = CompleteFuture::new();
+        self.send(RawCustomAsyncRwLockMessage::Upgrade {
+            future: future.get_handle(),
+            upgrade: |raw_lock| unsafe {
+                raw_lock
+                    .downcast_ref::<R>()
+                    .expect("Message sent to wrong lock")
+                    .upgrade()
+            },
+        });
+        future.await;
+    }
+}
+
+#[derive(Debug)]
+struct RawCustomAsyncRwLockInner<R, Q> {
+    raw_lock: R,
+    message_queue: Q,
+}
+impl<R, Q> RawCustomAsyncRwLockInner<R, Q>
+where
+    R: 'static + RawRwLock,
+{
+    fn handle_message(&self, message: RawCustomAsyncRwLockMessage) {
+        match message {
+            RawCustomAsyncRwLockMessage::AddReader(future) => {
+                self.raw_lock.add_reader();
+                match future.complete() {
+                    None => unsafe { self.raw_lock.remove_reader() },
+                    Some(true) => panic!("Future was completed already!"),
+                    Some(false) => {}
+                }
+            }
+            RawCustomAsyncRwLockMessage::AddWriter(future) => {
+                self.raw_lock.add_writer();
+                match future.complete() {
+                    None => unsafe { self.raw_lock.remove_writer() },
+                    Some(true) => panic!("Future was completed already!"),
+                    Some(false) => {}
+                }
+            }
+            RawCustomAsyncRwLockMessage::Upgrade { future, upgrade } => {
+                upgrade(&self.raw_lock);
+                match future.complete() {
+                    None => unsafe { self.raw_lock.remove_writer() },
+                    Some(true) => panic!("Future was completed already!"),
+                    Some(false) => {}
+                }
+            }
+        }
+    }
+}
+/// The message used for [`RawCustomAsyncRwLock`]
+#[derive(Debug)]
+pub enum RawCustomAsyncRwLockMessage {
+    /// A read operation
+    AddReader(CompleteFutureHandle),
+    /// A write operation
+    AddWriter(CompleteFutureHandle),
+    /// An upgrade operation, only sent when the raw lock is a [`RawUpgradeRwLock`]
+    Upgrade {
+        /// The future the upgrade is for
+        future: CompleteFutureHandle,
+        /// Calls [`RawUpgradeRwLock::upgrade`] on the converter's raw lock, which is passed erased
+        /// as the converter does not require [`RawUpgradeRwLock`]
+        upgrade: fn(&dyn Any),
+    },
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::blocking_bridge::BlockingBridge;
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{
+        AsyncRwLock, AsyncUpgradeReadGuard, CustomAsyncRwLock, RawCustomAsyncRwLock, RawSpinRwLock,
+        TryRwLock,
+    };
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::future::Future;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::task::{Context, Poll, Wake};
+    #[cfg(feature = "std")]
+    use std::thread::{current, park, scope, sleep, spawn, Thread};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    struct ThreadWaker(Thread);
+    #[cfg(feature = "std")]
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+    #[cfg(feature = "std")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(ThreadWaker(current())).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(out) = future.as_mut().poll(&mut context) {
+                return out;
+            }
+            park();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn custom_async_rw_lock_test() {
+        let (raw, _handle) = RawCustomAsyncRwLock::new::<StdThreadFunctions>(
+            RawSpinRwLock::<StdThreadFunctions>::default(),
+            ParkQueueStd::default(),
+        )
+        .expect("Could not spawn");
+        let lock = Arc::new(CustomAsyncRwLock::from_raw(raw, 100usize));
+
+        let read_guard = block_on(lock.read_async());
+        let lock_clone = lock.clone();
+        let handle = spawn(move || *block_on(lock_clone.write_async()) += 1);
+        sleep(Duration::from_millis(50));
+        assert!(!handle.is_finished());
+        assert_eq!(*read_guard, 100);
+        drop(read_guard);
+        handle.join().expect("Could not join");
+
+        let read_guard = block_on(lock.read_async());
+        let other_reader = lock.try_read().expect("Could not read");
+        scope(|scope| {
+            let handle = scope.spawn(move || {
+                let mut write_guard = block_on(read_guard.upgrade_async());
+                *write_guard += 1;
+            });
+            sleep(Duration::from_millis(50));
+            assert!(!handle.is_finished());
+            assert_eq!(*other_reader, 101);
+            drop(other_reader);
+        });
+        assert_eq!(*block_on(lock.read_async()), 102);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn bridge_test() {
+        let (bridge, handles) =
+            BlockingBridge::new::<StdThreadFunctions>(2, ParkQueueStd::default())
+                .expect("Could not spawn");
+        let lock = CustomAsyncRwLock::from_raw(
+            RawCustomAsyncRwLock::from_bridge(
+                RawSpinRwLock::<StdThreadFunctions>::default(),
+                bridge,
+            ),
+            0usize,
+        );
+        *block_on(lock.write_async()) += 1;
+        assert_eq!(*block_on(lock.read_async()), 1);
+        drop(lock);
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use core::ops::{Deref, DerefMut};
+use core::time::Duration;
+
+/// A non-blocking rwlock whose locking can fail, such as by being poisoned.
+pub trait TryFallibleRwLock<'a> {
+    /// The item stored by this lock
+    type Item: ?Sized;
+    /// The guard for reading from this lock
+    type ReadGuard: Deref<Target = Self::Item>;
+    /// The guard for writing to this lock
+    type WriteGuard: DerefMut<Target = Self::Item>;
+    /// The error returned when reading fails
+    type ReadError;
+    /// The error returned when writing fails
+    type WriteError;
+
+    /// Tries to read from the lock, returning `Ok(None)` if cannot immediately
+    fn try_read_fallible(&'a self) -> Result<Option<Self::ReadGuard>, Self::ReadError>;
+
+    /// Tries to write to the lock, returning `Ok(None)` if cannot immediately
+    fn try_write_fallible(&'a self) -> Result<Option<Self::WriteGuard>, Self::WriteError>;
+}
+/// A rwlock whose locking can fail, such as by being poisoned.
+pub trait FallibleRwLock<'a>: TryFallibleRwLock<'a> {
+    /// Reads from the lock, blocking until able or failed.
+    fn read_fallible(&'a self) -> Result<Self::ReadGuard, Self::ReadError>;
+
+    /// Writes to the lock, blocking until able or failed.
+    fn write_fallible(&'a self) -> Result<Self::WriteGuard, Self::WriteError>;
+}
+/// A rwlock that can be timed out on whose locking can fail.
+pub trait TimeoutFallibleRwLock<'a>: TryFallibleRwLock<'a> {
+    /// Reads from the lock with a timeout. Returns `Ok(None)` on timeout.
+    fn read_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadGuard>, Self::ReadError>;
+
+    /// Writes to the lock with a timeout. Returns `Ok(None)` on timeout.
+    fn write_timeout_fallible(
+        &'a self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteGuard>, Self::WriteError>;
+}
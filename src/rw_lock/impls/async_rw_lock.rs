@@ -0,0 +1,376 @@
+use crate::mutex::{AtomicMutex, CustomMutexGuard, RawAtomicMutex, TryMutex};
+use crate::queue::TryQueue;
+use crate::rw_lock::{
+    CustomRwLock, RawAsyncRwLock, RawAsyncUpgradeRwLock, RawAtomicRwLock, RawDowngradeRwLock,
+    RawTryRwLock, RawTryUpgradeRwLock,
+};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use core::hint::spin_loop;
+use core::sync::atomic::Ordering;
+use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
+
+/// A read-write lock that can only be accessed through async await or try
+/// operations.
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::ops::Deref;
+/// use concurrency_traits::rw_lock::{FullAsyncRwLock, AsyncRwLock};
+/// use std::task::{Context, Waker, Wake};
+/// use std::sync::Arc;
+/// use std::future::Future;
+/// use concurrency_traits::queue::ParkQueueStd;
+///
+/// struct NullWaker;
+/// impl Wake for NullWaker{
+///     fn wake(self:Arc<Self>) {
+///         println!("Wake!");
+///     }
+/// }
+///
+/// let mut future = Box::pin(async move {
+///     let lock = FullAsyncRwLock::<_, ParkQueueStd<_>>::new(100usize);
+///     let read_guard = lock.read_async().await;
+///     let read_guard2 = lock.read_async().await;
+///     assert_eq!(*read_guard.deref(), *read_guard2.deref());
+///     drop((read_guard, read_guard2));
+///     *lock.write_async().await += 1;
+///     assert_eq!(*lock.read_async().await, 101usize);
+/// });
+///
+/// assert!(!future.as_mut().poll(&mut Context::from_waker(&Arc::new(NullWaker).into())).is_pending())
+/// # }
+/// ```
+pub type FullAsyncRwLock<T, Q> = CustomRwLock<T, RawFullAsyncRwLock<Q>>;
+
+/// The raw portion of [`FullAsyncRwLock`].
+///
+/// Waiting readers, writers, and upgraders are queued as
+/// [`CompleteFutureHandle`]s and are handed the lock directly. When a writer
+/// releases all waiting readers are granted together.
+#[derive(Debug)]
+pub struct RawFullAsyncRwLock<Q> {
+    lock: RawAtomicRwLock,
+    /// Serializes queueing and handing off the lock
+    handoff: AtomicMutex<()>,
+    reader_queue: Q,
+    writer_queue: Q,
+    upgrade_queue: Q,
+}
+impl<Q> Default for RawFullAsyncRwLock<Q>
+where
+    Q: Default,
+{
+    fn default() -> Self {
+        Self {
+            lock: RawAtomicRwLock::default(),
+            handoff: AtomicMutex::default(),
+            reader_queue: Q::default(),
+            writer_queue: Q::default(),
+            upgrade_queue: Q::default(),
+        }
+    }
+}
+impl<Q> RawFullAsyncRwLock<Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    fn lock_handoff(&self) -> CustomMutexGuard<'_, (), RawAtomicMutex> {
+        loop {
+            if let Some(guard) = self.handoff.try_lock() {
+                return guard;
+            }
+            spin_loop();
+        }
+    }
+
+    /// Hands the lock to waiters that can take it in its current state. Must
+    /// be called while holding `handoff`.
+    fn dispatch(&self, mut prefer_readers: bool) {
+        loop {
+            let progressed = match self.lock.read_count.load(Ordering::Acquire) {
+                0 => false,
+                1 if prefer_readers => self.grant_readers() || self.grant_writer(),
+                1 => self.grant_writer() || self.grant_readers(),
+                2 => self.grant_upgrader() || self.grant_readers(),
+                _ => self.grant_readers(),
+            };
+            if !progressed {
+                return;
+            }
+            prefer_readers = false;
+        }
+    }
+
+    fn grant_writer(&self) -> bool {
+        if self
+            .lock
+            .read_count
+            .compare_exchange(1, 0, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return false;
+        }
+        while let Some(handle) = self.writer_queue.try_pop() {
+            if grant(handle) {
+                return true;
+            }
+        }
+        self.lock.read_count.store(1, Ordering::Release);
+        false
+    }
+
+    fn grant_upgrader(&self) -> bool {
+        if self
+            .lock
+            .read_count
+            .compare_exchange(2, 0, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return false;
+        }
+        while let Some(handle) = self.upgrade_queue.try_pop() {
+            if grant(handle) {
+                return true;
+            }
+        }
+        self.lock.read_count.store(2, Ordering::Release);
+        false
+    }
+
+    fn grant_readers(&self) -> bool {
+        // Hold a reader ourselves so the lock cannot open while granting
+        if !self.lock.try_add_reader() {
+            return false;
+        }
+        let mut granted = false;
+        while let Some(handle) = self.reader_queue.try_pop() {
+            self.lock.read_count.fetch_add(1, Ordering::AcqRel);
+            if grant(handle) {
+                granted = true;
+            } else {
+                self.lock.read_count.fetch_sub(1, Ordering::AcqRel);
+            }
+        }
+        unsafe { self.lock.remove_reader() }
+        granted
+    }
+
+    async fn wait(&self, queue: &Q, try_acquire: impl FnOnce() -> bool, kind: WaiterKind) {
+        let future = CompleteFuture::new();
+        {
+            let _guard = self.lock_handoff();
+            if try_acquire() {
+                return;
+            }
+            queue
+                .try_push(future.get_handle())
+                .unwrap_or_else(|_| panic!("Could not push handle!"));
+        }
+        let mut waiter = Waiter {
+            lock: self,
+            future,
+            kind: Some(kind),
+        };
+        (&mut waiter.future).await;
+        waiter.kind = None;
+    }
+}
+unsafe impl<Q> RawTryRwLock for RawFullAsyncRwLock<Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    #[inline]
+    fn try_add_reader(&self) -> bool {
+        self.lock.try_add_reader()
+    }
+
+    #[inline]
+    fn try_add_writer(&self) -> bool {
+        self.lock.try_add_writer()
+    }
+
+    unsafe fn remove_reader(&self) {
+        let _guard = self.lock_handoff();
+        self.lock.remove_reader();
+        self.dispatch(false);
+    }
+
+    unsafe fn remove_writer(&self) {
+        let _guard = self.lock_handoff();
+        self.lock.remove_writer();
+        self.dispatch(true);
+    }
+}
+#[async_trait]
+unsafe impl<Q> RawAsyncRwLock for RawFullAsyncRwLock<Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle> + Sync,
+{
+    async fn add_reader_async(&self) {
+        self.wait(
+            &self.reader_queue,
+            || self.try_add_reader(),
+            WaiterKind::Reader,
+        )
+        .await
+    }
+
+    async fn add_writer_async(&self) {
+        self.wait(
+            &self.writer_queue,
+            || self.try_add_writer(),
+            WaiterKind::Writer,
+        )
+        .await
+    }
+}
+unsafe impl<Q> RawTryUpgradeRwLock for RawFullAsyncRwLock<Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    #[inline]
+    unsafe fn try_upgrade(&self) -> bool {
+        self.lock.try_upgrade()
+    }
+}
+#[async_trait]
+unsafe impl<Q> RawAsyncUpgradeRwLock for RawFullAsyncRwLock<Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle> + Sync,
+{
+    async unsafe fn upgrade_async(&self) {
+        self.wait(
+            &self.upgrade_queue,
+            || self.try_upgrade(),
+            WaiterKind::Upgrader,
+        )
+        .await
+    }
+}
+unsafe impl<Q> RawDowngradeRwLock for RawFullAsyncRwLock<Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    unsafe fn downgrade(&self) {
+        let _guard = self.lock_handoff();
+        self.lock.downgrade();
+        self.dispatch(true);
+    }
+}
+
+/// Completes a waiter's future, returning true if it will take the lock.
+/// Futures that were dropped or cancelled will not.
+fn grant(handle: CompleteFutureHandle) -> bool {
+    handle.complete() == Some(false)
+}
+
+#[derive(Debug)]
+enum WaiterKind {
+    Reader,
+    Writer,
+    Upgrader,
+}
+
+/// Returns the lock if the waiting future is dropped after being granted.
+#[derive(Debug)]
+struct Waiter<'a, Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    lock: &'a RawFullAsyncRwLock<Q>,
+    future: CompleteFuture,
+    /// [`None`] once the lock has been taken
+    kind: Option<WaiterKind>,
+}
+impl<'a, Q> Drop for Waiter<'a, Q>
+where
+    Q: TryQueue<Item = CompleteFutureHandle>,
+{
+    fn drop(&mut self) {
+        if let Some(kind) = &self.kind {
+            // Returns true if already granted, otherwise cancels the grant
+            if self.future.complete() {
+                unsafe {
+                    match kind {
+                        WaiterKind::Reader => self.lock.remove_reader(),
+                        WaiterKind::Writer => self.lock.remove_writer(),
+                        // Still holds the original reader
+                        WaiterKind::Upgrader => self.lock.downgrade(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::queue::ParkQueueStd;
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{AsyncRwLock, AsyncUpgradeReadGuard, FullAsyncRwLock, TryRwLock};
+    #[cfg(feature = "std")]
+    use std::future::Future;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::task::{Context, Poll, Wake};
+    #[cfg(feature = "std")]
+    use std::thread::{self, Thread};
+
+    #[cfg(feature = "std")]
+    struct ThreadWaker(Thread);
+    #[cfg(feature = "std")]
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        loop {
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(out) => return out,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn full_async_rw_lock_test() {
+        let lock = Arc::new(FullAsyncRwLock::<_, ParkQueueStd<_>>::new(0usize));
+        let write_guard = block_on(lock.write_async());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                thread::spawn(move || block_on(async { *lock.read_async().await }))
+            })
+            .collect();
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || block_on(async { *lock.write_async().await += 1 }))
+        };
+        thread::sleep(std::time::Duration::from_millis(10));
+        let mut write_guard = write_guard;
+        *write_guard = 100;
+        drop(write_guard);
+        for handle in handles {
+            assert_eq!(handle.join().expect("Could not join"), 100);
+        }
+        writer.join().expect("Could not join");
+
+        let read_guard = block_on(lock.read_async());
+        assert_eq!(*read_guard, 101);
+        let mut write_guard = block_on(read_guard.upgrade_async());
+        assert!(lock.try_read().is_none());
+        *write_guard += 1;
+        drop(write_guard);
+        assert_eq!(*block_on(lock.read_async()), 102);
+    }
+}
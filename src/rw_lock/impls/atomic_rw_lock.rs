@@ -1,26 +1,108 @@
-use crate::rw_lock::{CustomRwLock, RawDowngradeRwLock, RawTryRwLock, RawTryUpgradeRwLock};
-use core::sync::atomic::{AtomicUsize, Ordering};
+use crate::rw_lock::{
+    CustomRwLock, RawDowngradeRwLock, RawReadoutRwLock, RawTryRwLock, RawTryUpgradableRwLock,
+    RawTryUpgradeRwLock, ReaderPreferring, RwLockPolicy,
+};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 /// A read-write lock that only supports try operations ([`TryRwLock`](crate::rw_lock::TryRwLock)).
-pub type AtomicRwLock<T> = CustomRwLock<T, RawAtomicRwLock>;
+pub type AtomicRwLock<T, P = ReaderPreferring> = CustomRwLock<T, RawAtomicRwLock<P>>;
 
-/// The raw portion of [`AtomicRwLock`].
+/// The raw portion of [`AtomicRwLock`]. `P` is the [`RwLockPolicy`] used to
+/// decide between readers and waiting writers.
 #[derive(Debug)]
-pub struct RawAtomicRwLock {
+pub struct RawAtomicRwLock<P = ReaderPreferring> {
     /// 0 is writing,
     /// 1 is open,
     /// x is x - 1 readers,
     pub(in crate::rw_lock) read_count: AtomicUsize,
+    /// Set while the upgradable reader is held, it is also counted as a reader
+    upgradable: AtomicBool,
+    /// Writers blocked on this lock, only tracked if [`RwLockPolicy::BLOCK_READERS`]
+    writers_waiting: AtomicUsize,
+    /// Readers blocked on this lock, only tracked if [`RwLockPolicy::READER_PHASES`]
+    readers_waiting: AtomicUsize,
+    /// Set while waiting readers are being let in before the next writer
+    reader_phase: AtomicBool,
+    phantom_policy: PhantomData<fn() -> P>,
 }
-impl Default for RawAtomicRwLock {
+impl<P> Default for RawAtomicRwLock<P> {
     fn default() -> Self {
         Self {
             read_count: AtomicUsize::new(1),
+            upgradable: AtomicBool::new(false),
+            writers_waiting: AtomicUsize::new(0),
+            readers_waiting: AtomicUsize::new(0),
+            reader_phase: AtomicBool::new(false),
+            phantom_policy: Default::default(),
         }
     }
 }
-unsafe impl RawTryRwLock for RawAtomicRwLock {
+impl<P> RawAtomicRwLock<P>
+where
+    P: RwLockPolicy,
+{
+    /// Whether the policy currently lets new readers in.
+    pub(in crate::rw_lock) fn readers_may_enter(&self) -> bool {
+        !P::BLOCK_READERS
+            || self.writers_waiting.load(Ordering::Acquire) == 0
+            || (P::READER_PHASES && self.reader_phase.load(Ordering::Acquire))
+    }
+
+    /// Whether the policy currently lets writers in.
+    pub(in crate::rw_lock) fn writers_may_enter(&self) -> bool {
+        !P::READER_PHASES || !self.reader_phase.load(Ordering::Acquire)
+    }
+
+    /// Marks a reader as blocked on this lock.
+    pub(in crate::rw_lock) fn start_waiting_reader(&self) {
+        if P::READER_PHASES {
+            self.readers_waiting.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    /// Unmarks a reader marked by [`RawAtomicRwLock::start_waiting_reader`].
+    pub(in crate::rw_lock) fn stop_waiting_reader(&self) {
+        if P::READER_PHASES && self.readers_waiting.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.reader_phase.store(false, Ordering::Release);
+        }
+    }
+
+    /// Marks a writer as blocked on this lock.
+    pub(in crate::rw_lock) fn start_waiting_writer(&self) {
+        if P::BLOCK_READERS {
+            self.writers_waiting.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    /// Unmarks a writer marked by [`RawAtomicRwLock::start_waiting_writer`].
+    pub(in crate::rw_lock) fn stop_waiting_writer(&self) {
+        if P::BLOCK_READERS {
+            self.writers_waiting.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    /// Starts a reader phase if readers are waiting. Called before releasing a
+    /// writer.
+    fn start_reader_phase(&self) {
+        if P::READER_PHASES && self.readers_waiting.load(Ordering::Acquire) > 0 {
+            self.reader_phase.store(true, Ordering::SeqCst);
+            // The last waiting reader may have left before the phase was set,
+            // which would leave it set with nobody to clear it
+            if self.readers_waiting.load(Ordering::SeqCst) == 0 {
+                self.reader_phase.store(false, Ordering::Release);
+            }
+        }
+    }
+}
+unsafe impl<P> RawTryRwLock for RawAtomicRwLock<P>
+where
+    P: RwLockPolicy,
+{
     fn try_add_reader(&self) -> bool {
+        if !self.readers_may_enter() {
+            return false;
+        }
         let mut count = self.read_count.load(Ordering::Acquire);
         loop {
             if count < 1 {
@@ -39,6 +121,9 @@ unsafe impl RawTryRwLock for RawAtomicRwLock {
     }
 
     fn try_add_writer(&self) -> bool {
+        if !self.writers_may_enter() {
+            return false;
+        }
         let mut count = self.read_count.load(Ordering::Acquire);
         loop {
             if count != 1 {
@@ -68,6 +153,7 @@ unsafe impl RawTryRwLock for RawAtomicRwLock {
     }
 
     unsafe fn remove_writer(&self) {
+        self.start_reader_phase();
         #[cfg(debug_assertions)]
         {
             assert_eq!(self.read_count.swap(1, Ordering::AcqRel), 0);
@@ -78,7 +164,41 @@ unsafe impl RawTryRwLock for RawAtomicRwLock {
         }
     }
 }
-unsafe impl RawTryUpgradeRwLock for RawAtomicRwLock {
+impl<P> RawReadoutRwLock for RawAtomicRwLock<P>
+where
+    P: RwLockPolicy,
+{
+    #[inline]
+    fn reader_count(&self) -> usize {
+        self.read_count.load(Ordering::Acquire).saturating_sub(1)
+    }
+
+    #[inline]
+    fn is_write_locked(&self) -> bool {
+        self.read_count.load(Ordering::Acquire) == 0
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.read_count.load(Ordering::Acquire) != 1
+    }
+
+    /// Only tracked if the policy tracks both waiting readers and writers.
+    fn waiter_count(&self) -> Option<usize> {
+        if P::BLOCK_READERS && P::READER_PHASES {
+            Some(
+                self.readers_waiting.load(Ordering::Acquire)
+                    + self.writers_waiting.load(Ordering::Acquire),
+            )
+        } else {
+            None
+        }
+    }
+}
+unsafe impl<P> RawTryUpgradeRwLock for RawAtomicRwLock<P>
+where
+    P: RwLockPolicy,
+{
     unsafe fn try_upgrade(&self) -> bool {
         let mut count = self.read_count.load(Ordering::Acquire);
         loop {
@@ -101,8 +221,47 @@ unsafe impl RawTryUpgradeRwLock for RawAtomicRwLock {
         }
     }
 }
-unsafe impl RawDowngradeRwLock for RawAtomicRwLock {
+unsafe impl<P> RawTryUpgradableRwLock for RawAtomicRwLock<P>
+where
+    P: RwLockPolicy,
+{
+    fn try_add_upgradable(&self) -> bool {
+        if self
+            .upgradable
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return false;
+        }
+        if self.try_add_reader() {
+            true
+        } else {
+            self.upgradable.store(false, Ordering::Release);
+            false
+        }
+    }
+
+    unsafe fn remove_upgradable(&self) {
+        self.remove_reader();
+        self.upgradable.store(false, Ordering::Release);
+    }
+
+    unsafe fn try_upgrade_upgradable(&self) -> bool {
+        // Only the upgradable reader can be left, no other upgrader can race us
+        if self.try_upgrade() {
+            self.upgradable.store(false, Ordering::Release);
+            true
+        } else {
+            false
+        }
+    }
+}
+unsafe impl<P> RawDowngradeRwLock for RawAtomicRwLock<P>
+where
+    P: RwLockPolicy,
+{
     unsafe fn downgrade(&self) {
+        self.start_reader_phase();
         #[cfg(debug_assertions)]
         {
             assert_eq!(self.read_count.swap(2, Ordering::AcqRel), 0);
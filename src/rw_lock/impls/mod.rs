@@ -1,6 +1,19 @@
+#[cfg(feature = "alloc")]
+mod async_rw_lock;
+#[cfg(feature = "alloc")]
+pub use async_rw_lock::*;
+
 mod atomic_rw_lock;
 pub use atomic_rw_lock::*;
 
+#[cfg(feature = "alloc")]
+mod park_rw_lock;
+#[cfg(feature = "alloc")]
+pub use park_rw_lock::*;
+
+#[cfg(feature = "impl_parking_lot")]
+mod rw_lock_parking_lot;
+
 mod spin_rw_lock;
 pub use spin_rw_lock::*;
 
@@ -0,0 +1,464 @@
+use crate::mutex::{CustomMutexGuard, Mutex, RawSpinLock, SpinLock};
+use crate::rw_lock::{
+    CustomRwLock, RawAtomicRwLock, RawDowngradeRwLock, RawRwLock, RawTimeoutRwLock, RawTryRwLock,
+    RawTryUpgradeRwLock, RawUpgradeRwLock, RawUpgradeTimeoutRwLock, ReaderPreferring, RwLockPolicy,
+};
+use crate::{ThreadFunctions, ThreadParker, ThreadTimeoutParker, TimeFunctions};
+use alloc::collections::VecDeque;
+use alloc::sync::{Arc, Weak};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+/// A [`ParkRwLock`] that uses std functions.
+#[cfg(feature = "std")]
+pub type ParkRwLockStd<T, P = ReaderPreferring> = ParkRwLock<T, crate::StdThreadFunctions, P>;
+
+/// A [`RawParkRwLock`] that uses std functions.
+#[cfg(feature = "std")]
+pub type RawParkRwLockStd<P = ReaderPreferring> = RawParkRwLock<crate::StdThreadFunctions, P>;
+
+/// A read-write lock that parks waiting threads. Uses [`RawParkRwLock`].
+pub type ParkRwLock<T, CS, P = ReaderPreferring> = CustomRwLock<T, RawParkRwLock<CS, P>>;
+
+/// The raw portion of [`ParkRwLock`].
+#[derive(Debug)]
+pub struct RawParkRwLock<CS, P = ReaderPreferring>
+where
+    CS: ThreadParker,
+{
+    lock: RawAtomicRwLock<P>,
+    inner: SpinLock<RawParkRwLockInner<CS>, CS>,
+}
+impl<CS, P> Default for RawParkRwLock<CS, P>
+where
+    CS: ThreadParker,
+{
+    fn default() -> Self {
+        Self {
+            lock: RawAtomicRwLock::default(),
+            inner: SpinLock::new(RawParkRwLockInner {
+                readers: VecDeque::new(),
+                writers: VecDeque::new(),
+                upgraders: VecDeque::new(),
+            }),
+        }
+    }
+}
+impl<CS, P> RawParkRwLock<CS, P>
+where
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    /// Wakes the parked threads that could acquire the lock in its current
+    /// state. Woken threads retry and re-park if they lose a race.
+    fn wake(&self, inner: &mut RawParkRwLockInner<CS>) {
+        match self.lock.read_count.load(Ordering::Acquire) {
+            // Writing, nobody can enter
+            0 => {}
+            // Open, a writer or any amount of readers can enter
+            1 => {
+                if self.lock.writers_may_enter() {
+                    wake_one::<CS>(&mut inner.writers);
+                }
+                if self.lock.readers_may_enter() {
+                    wake_all::<CS>(&mut inner.readers);
+                }
+            }
+            // A single reader that may be waiting to upgrade
+            2 if wake_one::<CS>(&mut inner.upgraders) => {}
+            _ => {
+                if self.lock.readers_may_enter() {
+                    wake_all::<CS>(&mut inner.readers)
+                }
+            }
+        }
+    }
+
+    /// Parks until `try_acquire` succeeds. `waiting` is called with true
+    /// once the thread starts waiting and with false once it stops.
+    fn park_until(
+        &self,
+        queue: impl Fn(&mut RawParkRwLockInner<CS>) -> &mut ParkerQueue<CS>,
+        try_acquire: impl Fn() -> bool,
+        waiting: impl Fn(bool),
+    ) {
+        let guard = self.inner.lock();
+        if try_acquire() {
+            return;
+        }
+        self.park_queued(guard, queue, try_acquire, waiting)
+    }
+
+    /// Joins the back of `queue` without trying to acquire first, then parks
+    /// until `try_acquire` succeeds.
+    fn park_queued<'a>(
+        &'a self,
+        mut guard: CustomMutexGuard<'a, RawParkRwLockInner<CS>, RawSpinLock<CS>>,
+        queue: impl Fn(&mut RawParkRwLockInner<CS>) -> &mut ParkerQueue<CS>,
+        try_acquire: impl Fn() -> bool,
+        waiting: impl Fn(bool),
+    ) {
+        waiting(true);
+        let parker = Arc::new((CS::current_thread(), AtomicBool::new(false)));
+        queue(&mut guard).push_back(Arc::downgrade(&parker));
+        loop {
+            drop(guard);
+            while !parker.1.load(Ordering::Acquire) {
+                CS::park();
+            }
+            guard = self.inner.lock();
+            if try_acquire() {
+                waiting(false);
+                return;
+            }
+            // Lost the race, keep our place at the front
+            parker.1.store(false, Ordering::Release);
+            queue(&mut guard).push_front(Arc::downgrade(&parker));
+        }
+    }
+}
+impl<CS, P> RawParkRwLock<CS, P>
+where
+    CS: ThreadTimeoutParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    fn park_until_timeout(
+        &self,
+        timeout: Duration,
+        queue: impl Fn(&mut RawParkRwLockInner<CS>) -> &mut ParkerQueue<CS>,
+        try_acquire: impl Fn() -> bool,
+        waiting: impl Fn(bool),
+    ) -> bool {
+        let end = CS::current_time() + timeout;
+        let mut guard = self.inner.lock();
+        if try_acquire() {
+            return true;
+        }
+        waiting(true);
+        let parker = Arc::new((CS::current_thread(), AtomicBool::new(false)));
+        queue(&mut guard).push_back(Arc::downgrade(&parker));
+        loop {
+            drop(guard);
+            while !parker.1.load(Ordering::Acquire) {
+                let current_time = CS::current_time();
+                if current_time >= end {
+                    break;
+                }
+                CS::park_timeout(end - current_time);
+            }
+            guard = self.inner.lock();
+            if try_acquire() {
+                waiting(false);
+                return true;
+            }
+            if CS::current_time() >= end {
+                // Dropping `parker` invalidates our place in the queue. No
+                // longer waiting may let others in.
+                waiting(false);
+                self.wake(&mut guard);
+                return false;
+            }
+            parker.1.store(false, Ordering::Release);
+            queue(&mut guard).push_front(Arc::downgrade(&parker));
+        }
+    }
+}
+unsafe impl<CS, P> RawTryRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    #[inline]
+    fn try_add_reader(&self) -> bool {
+        self.lock.try_add_reader()
+    }
+
+    #[inline]
+    fn try_add_writer(&self) -> bool {
+        self.lock.try_add_writer()
+    }
+
+    unsafe fn remove_reader(&self) {
+        let mut guard = self.inner.lock();
+        self.lock.remove_reader();
+        self.wake(&mut guard);
+    }
+
+    unsafe fn remove_writer(&self) {
+        let mut guard = self.inner.lock();
+        self.lock.remove_writer();
+        self.wake(&mut guard);
+    }
+}
+unsafe impl<CS, P> RawRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    fn add_reader(&self) {
+        self.park_until(
+            |inner| &mut inner.readers,
+            || self.try_add_reader(),
+            |waiting| reader_waiting(&self.lock, waiting),
+        )
+    }
+
+    fn add_writer(&self) {
+        self.park_until(
+            |inner| &mut inner.writers,
+            || self.try_add_writer(),
+            |waiting| writer_waiting(&self.lock, waiting),
+        )
+    }
+
+    /// Queues behind the threads it wakes so they go first.
+    unsafe fn bump_reader(&self) {
+        let mut guard = self.inner.lock();
+        if guard.is_empty() {
+            return;
+        }
+        self.lock.remove_reader();
+        self.wake(&mut guard);
+        self.park_queued(
+            guard,
+            |inner| &mut inner.readers,
+            || self.try_add_reader(),
+            |waiting| reader_waiting(&self.lock, waiting),
+        )
+    }
+
+    /// Queues behind the threads it wakes so they go first.
+    unsafe fn bump_writer(&self) {
+        let mut guard = self.inner.lock();
+        if guard.is_empty() {
+            return;
+        }
+        self.lock.remove_writer();
+        self.wake(&mut guard);
+        self.park_queued(
+            guard,
+            |inner| &mut inner.writers,
+            || self.try_add_writer(),
+            |waiting| writer_waiting(&self.lock, waiting),
+        )
+    }
+}
+unsafe impl<CS, P> RawTimeoutRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadTimeoutParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    fn add_reader_timeout(&self, timeout: Duration) -> bool {
+        self.park_until_timeout(
+            timeout,
+            |inner| &mut inner.readers,
+            || self.try_add_reader(),
+            |waiting| reader_waiting(&self.lock, waiting),
+        )
+    }
+
+    fn add_writer_timeout(&self, timeout: Duration) -> bool {
+        self.park_until_timeout(
+            timeout,
+            |inner| &mut inner.writers,
+            || self.try_add_writer(),
+            |waiting| writer_waiting(&self.lock, waiting),
+        )
+    }
+}
+unsafe impl<CS, P> RawTryUpgradeRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    #[inline]
+    unsafe fn try_upgrade(&self) -> bool {
+        self.lock.try_upgrade()
+    }
+}
+unsafe impl<CS, P> RawUpgradeRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    unsafe fn upgrade(&self) {
+        self.park_until(|inner| &mut inner.upgraders, || self.try_upgrade(), |_| {})
+    }
+}
+unsafe impl<CS, P> RawUpgradeTimeoutRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadTimeoutParker + ThreadFunctions + TimeFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    unsafe fn upgrade_timeout(&self, timeout: Duration) -> bool {
+        self.park_until_timeout(
+            timeout,
+            |inner| &mut inner.upgraders,
+            || self.try_upgrade(),
+            |_| {},
+        )
+    }
+}
+unsafe impl<CS, P> RawDowngradeRwLock for RawParkRwLock<CS, P>
+where
+    CS: ThreadParker + ThreadFunctions,
+    CS::ThreadId: Clone,
+    P: RwLockPolicy,
+{
+    unsafe fn downgrade(&self) {
+        let mut guard = self.inner.lock();
+        self.lock.downgrade();
+        self.wake(&mut guard);
+    }
+}
+
+fn reader_waiting<P>(lock: &RawAtomicRwLock<P>, waiting: bool)
+where
+    P: RwLockPolicy,
+{
+    if waiting {
+        lock.start_waiting_reader()
+    } else {
+        lock.stop_waiting_reader()
+    }
+}
+
+fn writer_waiting<P>(lock: &RawAtomicRwLock<P>, waiting: bool)
+where
+    P: RwLockPolicy,
+{
+    if waiting {
+        lock.start_waiting_writer()
+    } else {
+        lock.stop_waiting_writer()
+    }
+}
+
+/// True if should wake
+type ParkerQueue<CS> = VecDeque<Weak<(<CS as ThreadParker>::ThreadId, AtomicBool)>>;
+
+/// Wakes the first live parker in `queue`, returning true if one was woken.
+fn wake_one<CS>(queue: &mut ParkerQueue<CS>) -> bool
+where
+    CS: ThreadParker,
+    CS::ThreadId: Clone,
+{
+    while let Some(parker) = queue.pop_front() {
+        if let Some(parker) = parker.upgrade() {
+            parker.1.store(true, Ordering::Release);
+            CS::unpark(parker.0.clone());
+            return true;
+        }
+    }
+    false
+}
+
+fn wake_all<CS>(queue: &mut ParkerQueue<CS>)
+where
+    CS: ThreadParker,
+    CS::ThreadId: Clone,
+{
+    while wake_one::<CS>(queue) {}
+}
+
+#[derive(Debug)]
+struct RawParkRwLockInner<CS>
+where
+    CS: ThreadParker,
+{
+    readers: ParkerQueue<CS>,
+    writers: ParkerQueue<CS>,
+    upgraders: ParkerQueue<CS>,
+}
+impl<CS> RawParkRwLockInner<CS>
+where
+    CS: ThreadParker,
+{
+    fn is_empty(&self) -> bool {
+        self.readers.is_empty() && self.writers.is_empty() && self.upgraders.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{
+        CustomWriteGuard, DowngradeWriteGuard, ParkRwLockStd, RwLock, TimeoutRwLock, TryRwLock,
+        UpgradeReadGuard,
+    };
+    #[cfg(feature = "std")]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::{sleep, spawn};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn park_rw_lock_test() {
+        let lock = Arc::new(ParkRwLockStd::<usize>::new(0));
+        let read_count = Arc::new(AtomicUsize::new(0));
+
+        let write_guard = lock.write();
+        assert!(lock.try_read().is_none());
+        assert!(lock.read_timeout(Duration::from_millis(10)).is_none());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                let read_count = read_count.clone();
+                spawn(move || {
+                    assert_eq!(*lock.read(), 100);
+                    read_count.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        sleep(Duration::from_millis(10));
+        assert_eq!(read_count.load(Ordering::SeqCst), 0);
+        let mut write_guard = write_guard;
+        *write_guard = 100;
+        drop(write_guard);
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(read_count.load(Ordering::SeqCst), 4);
+
+        let read_guard = lock.read();
+        let lock_clone = lock.clone();
+        let handle = spawn(move || *lock_clone.write() += 1);
+        sleep(Duration::from_millis(10));
+        let mut write_guard = read_guard.upgrade();
+        *write_guard += 1;
+        let read_guard = write_guard.downgrade();
+        assert_eq!(*read_guard, 101);
+        drop(read_guard);
+        handle.join().expect("Could not join");
+        assert_eq!(*lock.read(), 102);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn bump_test() {
+        let lock = Arc::new(ParkRwLockStd::<usize>::new(0));
+        let mut write_guard = lock.write();
+        // Nothing waiting, keeps the lock
+        CustomWriteGuard::bump(&mut write_guard);
+        let lock_clone = lock.clone();
+        let handle = spawn(move || *lock_clone.write() += 1);
+        sleep(Duration::from_millis(50));
+        // The waiting thread goes first
+        CustomWriteGuard::bump(&mut write_guard);
+        assert_eq!(*write_guard, 1);
+        drop(write_guard);
+        handle.join().expect("Could not join");
+    }
+}
@@ -0,0 +1,132 @@
+use crate::rw_lock::{
+    DowngradeRwLock, DowngradeWriteGuard, RwLock, RwLockSized, TimeoutRwLock, TimeoutRwLockSized,
+    TryRwLock, TryRwLockSized, TryUpgradableRwLock, TryUpgradeReadGuard, UpgradableRwLock,
+    UpgradeReadGuard, UpgradeTimeoutReadGuard,
+};
+use parking_lot::{RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+impl<'a, T: ?Sized> TryRwLock<'a> for parking_lot::RwLock<T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type ReadGuard = RwLockReadGuard<'a, T>;
+    type WriteGuard = RwLockWriteGuard<'a, T>;
+
+    fn try_read(&'a self) -> Option<Self::ReadGuard> {
+        self.try_read()
+    }
+
+    fn try_write(&'a self) -> Option<Self::WriteGuard> {
+        self.try_write()
+    }
+}
+impl<'a, T> TryRwLockSized<'a> for parking_lot::RwLock<T> where T: 'a {}
+impl<'a, T: ?Sized> RwLock<'a> for parking_lot::RwLock<T>
+where
+    T: 'a,
+{
+    fn read(&'a self) -> Self::ReadGuard {
+        self.read()
+    }
+
+    fn write(&'a self) -> Self::WriteGuard {
+        self.write()
+    }
+}
+impl<'a, T> RwLockSized<'a> for parking_lot::RwLock<T> where T: 'a {}
+impl<'a, T: ?Sized> TimeoutRwLock<'a> for parking_lot::RwLock<T>
+where
+    T: 'a,
+{
+    fn read_timeout(&'a self, timeout: Duration) -> Option<Self::ReadGuard> {
+        self.try_read_for(timeout)
+    }
+
+    fn write_timeout(&'a self, timeout: Duration) -> Option<Self::WriteGuard> {
+        self.try_write_for(timeout)
+    }
+}
+impl<'a, T> TimeoutRwLockSized<'a> for parking_lot::RwLock<T> where T: 'a {}
+impl<'a, T: ?Sized> TryUpgradableRwLock<'a> for parking_lot::RwLock<T>
+where
+    T: 'a,
+{
+    type UpgradableGuard = RwLockUpgradableReadGuard<'a, T>;
+
+    fn try_upgradable_read(&'a self) -> Option<Self::UpgradableGuard> {
+        self.try_upgradable_read()
+    }
+}
+impl<'a, T: ?Sized> UpgradableRwLock<'a> for parking_lot::RwLock<T>
+where
+    T: 'a,
+{
+    fn upgradable_read(&'a self) -> Self::UpgradableGuard {
+        self.upgradable_read()
+    }
+}
+impl<'a, T: ?Sized> DowngradeRwLock<'a> for parking_lot::RwLock<T> where T: 'a {}
+
+impl<'a, T: ?Sized> TryUpgradeReadGuard<'a> for RwLockUpgradableReadGuard<'a, T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type WriteGuard = RwLockWriteGuard<'a, T>;
+
+    fn try_upgrade(self) -> Result<Self::WriteGuard, Self> {
+        RwLockUpgradableReadGuard::try_upgrade(self)
+    }
+}
+impl<'a, T: ?Sized> UpgradeReadGuard<'a> for RwLockUpgradableReadGuard<'a, T>
+where
+    T: 'a,
+{
+    fn upgrade(self) -> Self::WriteGuard {
+        RwLockUpgradableReadGuard::upgrade(self)
+    }
+}
+impl<'a, T: ?Sized> UpgradeTimeoutReadGuard<'a> for RwLockUpgradableReadGuard<'a, T>
+where
+    T: 'a,
+{
+    fn upgrade_timeout(self, timeout: Duration) -> Result<Self::WriteGuard, Self> {
+        RwLockUpgradableReadGuard::try_upgrade_for(self, timeout)
+    }
+}
+impl<'a, T: ?Sized> DowngradeWriteGuard<'a> for RwLockWriteGuard<'a, T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type ReadGuard = RwLockReadGuard<'a, T>;
+
+    fn downgrade(self) -> Self::ReadGuard {
+        RwLockWriteGuard::downgrade(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rw_lock::{
+        DowngradeWriteGuard, RwLock, TryRwLock, TryUpgradableRwLock, UpgradableRwLock,
+        UpgradeReadGuard,
+    };
+
+    #[test]
+    fn parking_lot_rw_lock_test() {
+        let lock = parking_lot::RwLock::new(0usize);
+        let upgradable = UpgradableRwLock::upgradable_read(&lock);
+        let read_guard = TryRwLock::try_read(&lock).expect("Readers should coexist");
+        assert!(TryUpgradableRwLock::try_upgradable_read(&lock).is_none());
+        drop(read_guard);
+        let mut write_guard = UpgradeReadGuard::upgrade(upgradable);
+        *write_guard += 1;
+        let read_guard = DowngradeWriteGuard::downgrade(write_guard);
+        assert_eq!(*read_guard, 1);
+        drop(read_guard);
+        assert_eq!(*RwLock::read(&lock), 1);
+    }
+}
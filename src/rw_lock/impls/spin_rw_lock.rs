@@ -1,21 +1,25 @@
+use crate::backoff::{Backoff, DefaultBackoff};
 use crate::rw_lock::{
-    CustomRwLock, RawAtomicRwLock, RawDowngradeRwLock, RawRwLock, RawTimeoutRwLock, RawTryRwLock,
-    RawTryUpgradeRwLock, RawUpgradeRwLock, RawUpgradeTimeoutRwLock,
+    CustomRwLock, RawAtomicRwLock, RawDowngradeRwLock, RawReadoutRwLock, RawRwLock,
+    RawTimeoutRwLock, RawTryRwLock, RawTryUpgradableRwLock, RawTryUpgradeRwLock,
+    RawUpgradableRwLock, RawUpgradeRwLock, RawUpgradeTimeoutRwLock, ReaderPreferring, RwLockPolicy,
 };
-use crate::{ThreadFunctions, TimeFunctions};
+use crate::TimeFunctions;
 use core::marker::PhantomData;
 use core::time::Duration;
 
 /// A read-write lock that spins to wait. Should only be locked for short durations.
-pub type SpinRwLock<T, CS> = CustomRwLock<T, RawSpinRwLock<CS>>;
+/// Waits between attempts with `B`.
+pub type SpinRwLock<T, CS, P = ReaderPreferring, B = DefaultBackoff<CS>> =
+    CustomRwLock<T, RawSpinRwLock<CS, P, B>>;
 
 /// The raw portion of [`SpinRwLock`].
 #[derive(Debug)]
-pub struct RawSpinRwLock<CS> {
-    lock: RawAtomicRwLock,
-    phantom_cs: PhantomData<fn() -> CS>,
+pub struct RawSpinRwLock<CS, P = ReaderPreferring, B = DefaultBackoff<CS>> {
+    lock: RawAtomicRwLock<P>,
+    phantom_cs: PhantomData<fn() -> (CS, B)>,
 }
-impl<CS> Default for RawSpinRwLock<CS> {
+impl<CS, P, B> Default for RawSpinRwLock<CS, P, B> {
     fn default() -> Self {
         Self {
             lock: Default::default(),
@@ -23,103 +27,294 @@ impl<CS> Default for RawSpinRwLock<CS> {
         }
     }
 }
-unsafe impl<CS> RawTryRwLock for RawSpinRwLock<CS> {
+unsafe impl<CS, P, B> RawTryRwLock for RawSpinRwLock<CS, P, B>
+where
+    P: RwLockPolicy,
+{
     #[inline]
     fn try_add_reader(&self) -> bool {
-        self.lock.try_add_reader()
+        let added = self.lock.try_add_reader();
+        #[cfg(feature = "deadlock_detection")]
+        if added {
+            crate::deadlock::acquired(self);
+        }
+        added
     }
 
     #[inline]
     fn try_add_writer(&self) -> bool {
-        self.lock.try_add_writer()
+        let added = self.lock.try_add_writer();
+        #[cfg(feature = "deadlock_detection")]
+        if added {
+            crate::deadlock::acquired(self);
+        }
+        added
     }
 
     #[inline]
     unsafe fn remove_reader(&self) {
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::released(self);
         self.lock.remove_reader()
     }
 
     #[inline]
     unsafe fn remove_writer(&self) {
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::released(self);
         self.lock.remove_writer()
     }
 }
-unsafe impl<CS> RawRwLock for RawSpinRwLock<CS>
+impl<CS, P, B> RawReadoutRwLock for RawSpinRwLock<CS, P, B>
+where
+    P: RwLockPolicy,
+{
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.lock.is_locked()
+    }
+
+    #[inline]
+    fn reader_count(&self) -> usize {
+        self.lock.reader_count()
+    }
+
+    #[inline]
+    fn is_write_locked(&self) -> bool {
+        self.lock.is_write_locked()
+    }
+
+    #[inline]
+    fn waiter_count(&self) -> Option<usize> {
+        self.lock.waiter_count()
+    }
+}
+unsafe impl<CS, P, B> RawRwLock for RawSpinRwLock<CS, P, B>
 where
-    CS: ThreadFunctions,
+    P: RwLockPolicy,
+    B: Backoff,
 {
     fn add_reader(&self) {
+        if self.try_add_reader() {
+            return;
+        }
+        self.lock.start_waiting_reader();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting(self);
+        let mut backoff = B::default();
         while !self.try_add_reader() {
-            CS::yield_now();
+            backoff.backoff();
         }
+        self.lock.stop_waiting_reader();
     }
 
     fn add_writer(&self) {
+        if self.try_add_writer() {
+            return;
+        }
+        self.lock.start_waiting_writer();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting(self);
+        let mut backoff = B::default();
         while !self.try_add_writer() {
-            CS::yield_now()
+            backoff.backoff();
         }
+        self.lock.stop_waiting_writer();
+    }
+
+    /// Backs off once between removing and re-adding so waiters can get in.
+    unsafe fn bump_reader(&self) {
+        self.remove_reader();
+        B::default().backoff();
+        self.add_reader();
+    }
+
+    /// Backs off once between removing and re-adding so waiters can get in.
+    unsafe fn bump_writer(&self) {
+        self.remove_writer();
+        B::default().backoff();
+        self.add_writer();
     }
 }
-unsafe impl<CS> RawTryUpgradeRwLock for RawSpinRwLock<CS> {
+unsafe impl<CS, P, B> RawTryUpgradeRwLock for RawSpinRwLock<CS, P, B>
+where
+    P: RwLockPolicy,
+{
     #[inline]
     unsafe fn try_upgrade(&self) -> bool {
         self.lock.try_upgrade()
     }
 }
-unsafe impl<CS> RawUpgradeRwLock for RawSpinRwLock<CS>
+unsafe impl<CS, P, B> RawUpgradeRwLock for RawSpinRwLock<CS, P, B>
 where
-    CS: ThreadFunctions,
+    P: RwLockPolicy,
+    B: Backoff,
 {
     unsafe fn upgrade(&self) {
+        if self.try_upgrade() {
+            return;
+        }
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting_upgrade(self);
+        let mut backoff = B::default();
         while !self.try_upgrade() {
-            CS::yield_now();
+            backoff.backoff();
         }
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::stopped_waiting();
     }
 }
-unsafe impl<CS> RawUpgradeTimeoutRwLock for RawSpinRwLock<CS>
+unsafe impl<CS, P, B> RawUpgradeTimeoutRwLock for RawSpinRwLock<CS, P, B>
 where
-    CS: ThreadFunctions + TimeFunctions,
+    CS: TimeFunctions,
+    P: RwLockPolicy,
+    B: Backoff,
 {
     unsafe fn upgrade_timeout(&self, timeout: Duration) -> bool {
+        if self.try_upgrade() {
+            return true;
+        }
         let end = CS::current_time() + timeout;
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting_upgrade(self);
+        let mut out = false;
+        let mut backoff = B::default();
         while end > CS::current_time() {
             if self.try_upgrade() {
-                return true;
+                out = true;
+                break;
             }
-            CS::yield_now();
+            backoff.backoff();
         }
-        false
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::stopped_waiting();
+        out
     }
 }
-unsafe impl<CS> RawDowngradeRwLock for RawSpinRwLock<CS> {
+unsafe impl<CS, P, B> RawTryUpgradableRwLock for RawSpinRwLock<CS, P, B>
+where
+    P: RwLockPolicy,
+{
+    #[inline]
+    fn try_add_upgradable(&self) -> bool {
+        let added = self.lock.try_add_upgradable();
+        #[cfg(feature = "deadlock_detection")]
+        if added {
+            crate::deadlock::acquired(self);
+        }
+        added
+    }
+
+    #[inline]
+    unsafe fn remove_upgradable(&self) {
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::released(self);
+        self.lock.remove_upgradable()
+    }
+
+    #[inline]
+    unsafe fn try_upgrade_upgradable(&self) -> bool {
+        self.lock.try_upgrade_upgradable()
+    }
+}
+unsafe impl<CS, P, B> RawUpgradableRwLock for RawSpinRwLock<CS, P, B>
+where
+    P: RwLockPolicy,
+    B: Backoff,
+{
+    fn add_upgradable(&self) {
+        if self.try_add_upgradable() {
+            return;
+        }
+        self.lock.start_waiting_reader();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting(self);
+        let mut backoff = B::default();
+        while !self.try_add_upgradable() {
+            backoff.backoff();
+        }
+        self.lock.stop_waiting_reader();
+    }
+
+    unsafe fn upgrade_upgradable(&self) {
+        if self.try_upgrade_upgradable() {
+            return;
+        }
+        // Keeps new readers out if the policy allows
+        self.lock.start_waiting_writer();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting_upgrade(self);
+        let mut backoff = B::default();
+        while !self.try_upgrade_upgradable() {
+            backoff.backoff();
+        }
+        self.lock.stop_waiting_writer();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::stopped_waiting();
+    }
+}
+unsafe impl<CS, P, B> RawDowngradeRwLock for RawSpinRwLock<CS, P, B>
+where
+    P: RwLockPolicy,
+{
     #[inline]
     unsafe fn downgrade(&self) {
         self.lock.downgrade()
     }
 }
-unsafe impl<CS> RawTimeoutRwLock for RawSpinRwLock<CS>
+unsafe impl<CS, P, B> RawTimeoutRwLock for RawSpinRwLock<CS, P, B>
 where
-    CS: ThreadFunctions + TimeFunctions,
+    CS: TimeFunctions,
+    P: RwLockPolicy,
+    B: Backoff,
 {
     fn add_reader_timeout(&self, timeout: Duration) -> bool {
+        if self.try_add_reader() {
+            return true;
+        }
         let end = CS::current_time() + timeout;
+        self.lock.start_waiting_reader();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting(self);
+        let mut out = false;
+        let mut backoff = B::default();
         while end > CS::current_time() {
             if self.try_add_reader() {
-                return true;
+                out = true;
+                break;
             }
-            CS::yield_now();
+            backoff.backoff();
         }
-        false
+        self.lock.stop_waiting_reader();
+        #[cfg(feature = "deadlock_detection")]
+        if !out {
+            crate::deadlock::stopped_waiting();
+        }
+        out
     }
 
     fn add_writer_timeout(&self, timeout: Duration) -> bool {
+        if self.try_add_writer() {
+            return true;
+        }
         let end = CS::current_time() + timeout;
+        self.lock.start_waiting_writer();
+        #[cfg(feature = "deadlock_detection")]
+        crate::deadlock::waiting(self);
+        let mut out = false;
+        let mut backoff = B::default();
         while end > CS::current_time() {
             if self.try_add_writer() {
-                return true;
+                out = true;
+                break;
             }
-            CS::yield_now();
+            backoff.backoff();
+        }
+        self.lock.stop_waiting_writer();
+        #[cfg(feature = "deadlock_detection")]
+        if !out {
+            crate::deadlock::stopped_waiting();
         }
-        false
+        out
     }
 }
@@ -1,6 +1,6 @@
 use crate::rw_lock::*;
 use std::sync;
-use std::sync::{RwLockReadGuard, RwLockWriteGuard, TryLockError};
+use std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard, TryLockError};
 
 impl<'a, T: ?Sized> TryRwLock<'a> for sync::RwLock<T>
 where
@@ -52,3 +52,41 @@ where
     }
 }
 impl<'a, T> RwLockSized<'a> for sync::RwLock<T> where T: 'a {}
+impl<'a, T: ?Sized> TryFallibleRwLock<'a> for sync::RwLock<T>
+where
+    T: 'a,
+{
+    type Item = T;
+    type ReadGuard = RwLockReadGuard<'a, T>;
+    type WriteGuard = RwLockWriteGuard<'a, T>;
+    type ReadError = PoisonError<RwLockReadGuard<'a, T>>;
+    type WriteError = PoisonError<RwLockWriteGuard<'a, T>>;
+
+    fn try_read_fallible(&'a self) -> Result<Option<Self::ReadGuard>, Self::ReadError> {
+        match self.try_read() {
+            Ok(guard) => Ok(Some(guard)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Poisoned(error)) => Err(error),
+        }
+    }
+
+    fn try_write_fallible(&'a self) -> Result<Option<Self::WriteGuard>, Self::WriteError> {
+        match self.try_write() {
+            Ok(guard) => Ok(Some(guard)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Poisoned(error)) => Err(error),
+        }
+    }
+}
+impl<'a, T: ?Sized> FallibleRwLock<'a> for sync::RwLock<T>
+where
+    T: 'a,
+{
+    fn read_fallible(&'a self) -> Result<Self::ReadGuard, Self::ReadError> {
+        self.read()
+    }
+
+    fn write_fallible(&'a self) -> Result<Self::WriteGuard, Self::WriteError> {
+        self.write()
+    }
+}
@@ -0,0 +1,397 @@
+use crate::rw_lock::{CustomReadGuard, CustomWriteGuard, RawTryRwLock};
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+/// A read guard that can be projected to a part of its data, keeping the
+/// reader.
+pub trait MapReadGuard<'a, U: ?Sized>: Sized + Deref {
+    /// The guard that is produced by mapping
+    type MappedGuard: Deref<Target = U>;
+
+    /// Makes a new guard for a component of the locked data.
+    fn map(guard: Self, func: impl FnOnce(&Self::Target) -> &U) -> Self::MappedGuard;
+
+    /// Tries to make a new guard for a component of the locked data, returning
+    /// the original guard if `func` returns [`None`].
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&Self::Target) -> Option<&U>,
+    ) -> Result<Self::MappedGuard, Self>;
+}
+/// A write guard that can be projected to a part of its data, keeping the
+/// writer.
+pub trait MapWriteGuard<'a, U: ?Sized>: Sized + DerefMut {
+    /// The guard that is produced by mapping
+    type MappedGuard: DerefMut<Target = U>;
+
+    /// Makes a new guard for a component of the locked data.
+    fn map(guard: Self, func: impl FnOnce(&mut Self::Target) -> &mut U) -> Self::MappedGuard;
+
+    /// Tries to make a new guard for a component of the locked data, returning
+    /// the original guard if `func` returns [`None`].
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<Self::MappedGuard, Self>;
+}
+
+impl<'a, T, R> CustomReadGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    /// Makes a [`MappedCustomReadGuard`] for a component of the locked data.
+    pub fn map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&T) -> &U,
+    ) -> MappedCustomReadGuard<'a, U, R> {
+        let data = func(unsafe { &*guard.lock.data.get() });
+        // Only forget the guard once `func` returned so a panic still unlocks
+        let guard = ManuallyDrop::new(guard);
+        MappedCustomReadGuard::new(&guard.lock.raw_lock, data)
+    }
+
+    /// Tries to make a [`MappedCustomReadGuard`] for a component of the locked
+    /// data, returning the original guard if `func` returns [`None`].
+    pub fn try_map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&T) -> Option<&U>,
+    ) -> Result<MappedCustomReadGuard<'a, U, R>, Self> {
+        match func(unsafe { &*guard.lock.data.get() }) {
+            None => Err(guard),
+            Some(data) => {
+                let guard = ManuallyDrop::new(guard);
+                Ok(MappedCustomReadGuard::new(&guard.lock.raw_lock, data))
+            }
+        }
+    }
+}
+impl<'a, T, U, R> MapReadGuard<'a, U> for CustomReadGuard<'a, T, R>
+where
+    U: 'a + ?Sized,
+    R: RawTryRwLock,
+{
+    type MappedGuard = MappedCustomReadGuard<'a, U, R>;
+
+    #[inline]
+    fn map(guard: Self, func: impl FnOnce(&Self::Target) -> &U) -> Self::MappedGuard {
+        Self::map(guard, func)
+    }
+
+    #[inline]
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&Self::Target) -> Option<&U>,
+    ) -> Result<Self::MappedGuard, Self> {
+        Self::try_map(guard, func)
+    }
+}
+
+impl<'a, T, R> CustomWriteGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    /// Makes a [`MappedCustomWriteGuard`] for a component of the locked data.
+    pub fn map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> &mut U,
+    ) -> MappedCustomWriteGuard<'a, U, R> {
+        let data = func(unsafe { &mut *guard.lock.data.get() });
+        let guard = ManuallyDrop::new(guard);
+        MappedCustomWriteGuard::new(&guard.lock.raw_lock, data)
+    }
+
+    /// Tries to make a [`MappedCustomWriteGuard`] for a component of the
+    /// locked data, returning the original guard if `func` returns [`None`].
+    pub fn try_map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> Option<&mut U>,
+    ) -> Result<MappedCustomWriteGuard<'a, U, R>, Self> {
+        match func(unsafe { &mut *guard.lock.data.get() }) {
+            None => Err(guard),
+            Some(data) => {
+                let guard = ManuallyDrop::new(guard);
+                Ok(MappedCustomWriteGuard::new(&guard.lock.raw_lock, data))
+            }
+        }
+    }
+}
+impl<'a, T, U, R> MapWriteGuard<'a, U> for CustomWriteGuard<'a, T, R>
+where
+    U: 'a + ?Sized,
+    R: RawTryRwLock,
+{
+    type MappedGuard = MappedCustomWriteGuard<'a, U, R>;
+
+    #[inline]
+    fn map(guard: Self, func: impl FnOnce(&mut Self::Target) -> &mut U) -> Self::MappedGuard {
+        Self::map(guard, func)
+    }
+
+    #[inline]
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<Self::MappedGuard, Self> {
+        Self::try_map(guard, func)
+    }
+}
+
+/// A read guard for a component of the data in a
+/// [`CustomRwLock`](crate::rw_lock::CustomRwLock). Removes the reader on drop.
+#[derive(Debug)]
+pub struct MappedCustomReadGuard<'a, T: ?Sized, R>
+where
+    R: RawTryRwLock,
+{
+    raw_lock: &'a R,
+    data: *const T,
+    phantom_data: PhantomData<&'a T>,
+}
+impl<'a, T: ?Sized, R> MappedCustomReadGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    fn new(raw_lock: &'a R, data: &'a T) -> Self {
+        Self {
+            raw_lock,
+            data,
+            phantom_data: PhantomData,
+        }
+    }
+
+    /// Makes a new [`MappedCustomReadGuard`] for a component of this guard's
+    /// data.
+    pub fn map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&T) -> &U,
+    ) -> MappedCustomReadGuard<'a, U, R> {
+        let data = func(unsafe { &*guard.data });
+        let guard = ManuallyDrop::new(guard);
+        MappedCustomReadGuard::new(guard.raw_lock, data)
+    }
+
+    /// Tries to make a new [`MappedCustomReadGuard`] for a component of this
+    /// guard's data, returning the original guard if `func` returns [`None`].
+    pub fn try_map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&T) -> Option<&U>,
+    ) -> Result<MappedCustomReadGuard<'a, U, R>, Self> {
+        match func(unsafe { &*guard.data }) {
+            None => Err(guard),
+            Some(data) => {
+                let guard = ManuallyDrop::new(guard);
+                Ok(MappedCustomReadGuard::new(guard.raw_lock, data))
+            }
+        }
+    }
+}
+impl<'a, T: ?Sized, U, R> MapReadGuard<'a, U> for MappedCustomReadGuard<'a, T, R>
+where
+    U: 'a + ?Sized,
+    R: RawTryRwLock,
+{
+    type MappedGuard = MappedCustomReadGuard<'a, U, R>;
+
+    #[inline]
+    fn map(guard: Self, func: impl FnOnce(&Self::Target) -> &U) -> Self::MappedGuard {
+        Self::map(guard, func)
+    }
+
+    #[inline]
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&Self::Target) -> Option<&U>,
+    ) -> Result<Self::MappedGuard, Self> {
+        Self::try_map(guard, func)
+    }
+}
+impl<'a, T: ?Sized, R> Deref for MappedCustomReadGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.data }
+    }
+}
+impl<'a, T: ?Sized, R> Drop for MappedCustomReadGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    fn drop(&mut self) {
+        unsafe { self.raw_lock.remove_reader() }
+    }
+}
+unsafe impl<'a, T: ?Sized, R> Send for MappedCustomReadGuard<'a, T, R>
+where
+    T: Sync,
+    R: RawTryRwLock + Sync,
+{
+}
+unsafe impl<'a, T: ?Sized, R> Sync for MappedCustomReadGuard<'a, T, R>
+where
+    T: Sync,
+    R: RawTryRwLock + Sync,
+{
+}
+
+/// A write guard for a component of the data in a
+/// [`CustomRwLock`](crate::rw_lock::CustomRwLock). Removes the writer on drop.
+#[derive(Debug)]
+pub struct MappedCustomWriteGuard<'a, T: ?Sized, R>
+where
+    R: RawTryRwLock,
+{
+    raw_lock: &'a R,
+    data: *mut T,
+    phantom_data: PhantomData<&'a mut T>,
+}
+impl<'a, T: ?Sized, R> MappedCustomWriteGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    fn new(raw_lock: &'a R, data: &'a mut T) -> Self {
+        Self {
+            raw_lock,
+            data,
+            phantom_data: PhantomData,
+        }
+    }
+
+    /// Makes a new [`MappedCustomWriteGuard`] for a component of this guard's
+    /// data.
+    pub fn map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> &mut U,
+    ) -> MappedCustomWriteGuard<'a, U, R> {
+        let data = func(unsafe { &mut *guard.data });
+        let guard = ManuallyDrop::new(guard);
+        MappedCustomWriteGuard::new(guard.raw_lock, data)
+    }
+
+    /// Tries to make a new [`MappedCustomWriteGuard`] for a component of this
+    /// guard's data, returning the original guard if `func` returns [`None`].
+    pub fn try_map<U: ?Sized>(
+        guard: Self,
+        func: impl FnOnce(&mut T) -> Option<&mut U>,
+    ) -> Result<MappedCustomWriteGuard<'a, U, R>, Self> {
+        match func(unsafe { &mut *guard.data }) {
+            None => Err(guard),
+            Some(data) => {
+                let guard = ManuallyDrop::new(guard);
+                Ok(MappedCustomWriteGuard::new(guard.raw_lock, data))
+            }
+        }
+    }
+}
+impl<'a, T: ?Sized, U, R> MapWriteGuard<'a, U> for MappedCustomWriteGuard<'a, T, R>
+where
+    U: 'a + ?Sized,
+    R: RawTryRwLock,
+{
+    type MappedGuard = MappedCustomWriteGuard<'a, U, R>;
+
+    #[inline]
+    fn map(guard: Self, func: impl FnOnce(&mut Self::Target) -> &mut U) -> Self::MappedGuard {
+        Self::map(guard, func)
+    }
+
+    #[inline]
+    fn try_map(
+        guard: Self,
+        func: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<Self::MappedGuard, Self> {
+        Self::try_map(guard, func)
+    }
+}
+impl<'a, T: ?Sized, R> Deref for MappedCustomWriteGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.data }
+    }
+}
+impl<'a, T: ?Sized, R> DerefMut for MappedCustomWriteGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.data }
+    }
+}
+impl<'a, T: ?Sized, R> Drop for MappedCustomWriteGuard<'a, T, R>
+where
+    R: RawTryRwLock,
+{
+    fn drop(&mut self) {
+        unsafe { self.raw_lock.remove_writer() }
+    }
+}
+unsafe impl<'a, T: ?Sized, R> Send for MappedCustomWriteGuard<'a, T, R>
+where
+    T: Send,
+    R: RawTryRwLock + Sync,
+{
+}
+unsafe impl<'a, T: ?Sized, R> Sync for MappedCustomWriteGuard<'a, T, R>
+where
+    T: Sync,
+    R: RawTryRwLock + Sync,
+{
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{CustomReadGuard, CustomWriteGuard, MapReadGuard, SpinRwLock, TryRwLock};
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mapped_rw_lock_guard_test() {
+        let lock = SpinRwLock::<_, StdThreadFunctions>::new((0usize, vec![1usize, 2, 3]));
+        let read_guard =
+            CustomReadGuard::map(lock.try_read().expect("Could not read"), |data| &data.1);
+        let read_guard = MapReadGuard::map(read_guard, |data: &Vec<usize>| &data[2]);
+        assert_eq!(*read_guard, 3);
+        assert!(lock.try_write().is_none());
+        drop(read_guard);
+
+        let write_guard = lock.try_write().expect("Could not write");
+        let write_guard = CustomWriteGuard::try_map(write_guard, |data| data.1.get_mut(10))
+            .expect_err("Should not map");
+        let mut write_guard = CustomWriteGuard::map(write_guard, |data| &mut data.0);
+        *write_guard = 10;
+        assert!(lock.try_read().is_none());
+        drop(write_guard);
+        assert_eq!(lock.try_read().expect("Could not read").0, 10);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn map_panic_test() {
+        let lock = SpinRwLock::<_, StdThreadFunctions>::new(0usize);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            CustomWriteGuard::map(
+                lock.try_write().expect("Could not write"),
+                |_| -> &mut usize { panic!("map panicked") },
+            )
+        }));
+        assert!(result.is_err());
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            CustomReadGuard::map(lock.try_read().expect("Could not read"), |_| -> &usize {
+                panic!("map panicked")
+            })
+        }));
+        assert!(result.is_err());
+        assert!(lock.try_write().is_some());
+    }
+}
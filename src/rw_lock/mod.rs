@@ -13,15 +13,42 @@ mod async_timout;
 #[cfg(feature = "alloc")]
 pub use async_timout::*;
 
+#[cfg(feature = "alloc")]
+mod arc;
+#[cfg(feature = "alloc")]
+pub use arc::*;
+
 mod custom;
 pub use custom::*;
 
+#[cfg(feature = "alloc")]
+mod custom_async;
+#[cfg(feature = "alloc")]
+pub use custom_async::*;
+
+mod fallible;
+pub use fallible::*;
+
+mod mapped;
+pub use mapped::*;
+
+mod policy;
+pub use policy::*;
+
+mod readout;
+pub use readout::*;
+
 mod timeout;
 pub use timeout::*;
 
 mod r#try;
 pub use r#try::*;
 
+mod upgradable_rw_lock;
+pub use upgradable_rw_lock::*;
+
+mod unlocked;
+
 mod upgrade_rw_lock;
 pub use upgrade_rw_lock::*;
 
@@ -33,6 +60,26 @@ pub unsafe trait RawRwLock: RawTryRwLock {
     fn add_reader(&self);
     /// Blocks until a writer is added to this lock
     fn add_writer(&self);
+
+    /// Removes and re-adds a reader, letting a waiting thread go first if the
+    /// lock supports it.
+    ///
+    /// # Safety
+    /// Same as [`RawTryRwLock::remove_reader`].
+    unsafe fn bump_reader(&self) {
+        self.remove_reader();
+        self.add_reader();
+    }
+
+    /// Removes and re-adds a writer, letting a waiting thread go first if the
+    /// lock supports it.
+    ///
+    /// # Safety
+    /// Same as [`RawTryRwLock::remove_writer`].
+    unsafe fn bump_writer(&self) {
+        self.remove_writer();
+        self.add_writer();
+    }
 }
 /// A generic blocking reader-writer lock trait
 ///
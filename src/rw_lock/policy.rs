@@ -0,0 +1,114 @@
+/// Decides whether readers or writers take precedence in a raw rw lock.
+///
+/// Writers blocked on a lock mark it as having pending writers and readers
+/// blocked on a writer mark it as having waiting readers. Try operations
+/// consult the policy with that state.
+pub trait RwLockPolicy {
+    /// If true new readers are refused while writers are waiting.
+    const BLOCK_READERS: bool;
+    /// If true readers that were waiting when a writer releases are all let in
+    /// before the next writer.
+    const READER_PHASES: bool;
+}
+
+/// Readers can always join other readers. Writers can be starved by a
+/// continuous stream of readers.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ReaderPreferring;
+impl RwLockPolicy for ReaderPreferring {
+    const BLOCK_READERS: bool = false;
+    const READER_PHASES: bool = false;
+}
+
+/// New readers are refused while a writer is waiting. Readers can be starved by
+/// a continuous stream of writers.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct WriterPreferring;
+impl RwLockPolicy for WriterPreferring {
+    const BLOCK_READERS: bool = true;
+    const READER_PHASES: bool = false;
+}
+
+/// Read and write phases alternate. New readers are refused while a writer is
+/// waiting and readers that waited through a write are let in before the next
+/// writer. Neither side can be starved.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PhaseFair;
+impl RwLockPolicy for PhaseFair {
+    const BLOCK_READERS: bool = true;
+    const READER_PHASES: bool = true;
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{
+        ParkRwLockStd, PhaseFair, RwLock, RwLockPolicy, SpinRwLock, TimeoutRwLock, WriterPreferring,
+    };
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::sync::atomic::{AtomicBool, Ordering};
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::{sleep, spawn};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    /// Keeps the lock continuously read locked while a writer tries to get in.
+    #[cfg(feature = "std")]
+    fn writer_progress_test<L>(lock: L)
+    where
+        L: 'static + Send + Sync,
+        for<'a> L: RwLock<'a, Item = usize> + TimeoutRwLock<'a>,
+    {
+        let lock = Arc::new(lock);
+        let stop = Arc::new(AtomicBool::new(false));
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                let stop = stop.clone();
+                spawn(move || {
+                    while !stop.load(Ordering::Acquire) {
+                        let guard = lock.read();
+                        sleep(Duration::from_millis(1));
+                        drop(guard);
+                    }
+                })
+            })
+            .collect();
+        sleep(Duration::from_millis(10));
+        for _ in 0..10 {
+            *lock
+                .write_timeout(Duration::from_secs(5))
+                .expect("Writer was starved") += 1;
+        }
+        stop.store(true, Ordering::Release);
+        for reader in readers {
+            reader.join().expect("Could not join");
+        }
+        assert_eq!(*lock.read(), 10);
+    }
+
+    #[cfg(feature = "std")]
+    fn policy_test<P>()
+    where
+        P: 'static + RwLockPolicy,
+    {
+        writer_progress_test(SpinRwLock::<_, StdThreadFunctions, P>::new(0));
+        writer_progress_test(ParkRwLockStd::<_, P>::new(0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn writer_preferring_test() {
+        policy_test::<WriterPreferring>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn phase_fair_test() {
+        policy_test::<PhaseFair>();
+    }
+}
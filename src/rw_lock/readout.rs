@@ -0,0 +1,98 @@
+use crate::rw_lock::{CustomRwLock, RawTryRwLock};
+
+/// A raw rw lock whose state can be read.
+pub trait RawReadoutRwLock: RawTryRwLock {
+    /// Whether the lock is currently held by any readers or a writer.
+    fn is_locked(&self) -> bool {
+        self.is_write_locked() || self.reader_count() > 0
+    }
+
+    /// The number of readers currently holding the lock.
+    fn reader_count(&self) -> usize;
+
+    /// Whether the lock is currently held by a writer.
+    fn is_write_locked(&self) -> bool;
+
+    /// The number of threads or tasks waiting on the lock, [`None`] if not
+    /// tracked.
+    fn waiter_count(&self) -> Option<usize> {
+        None
+    }
+}
+/// A rw lock whose state can be read.
+pub trait ReadoutRwLock {
+    /// Whether the lock is currently held by any readers or a writer.
+    fn is_locked(&self) -> bool;
+
+    /// The number of readers currently holding the lock.
+    fn reader_count(&self) -> usize;
+
+    /// Whether the lock is currently held by a writer.
+    fn is_write_locked(&self) -> bool;
+
+    /// The number of threads or tasks waiting on the lock, [`None`] if not
+    /// tracked.
+    fn waiter_count(&self) -> Option<usize>;
+}
+
+impl<T, R> ReadoutRwLock for CustomRwLock<T, R>
+where
+    R: RawReadoutRwLock,
+{
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.raw_lock.is_locked()
+    }
+
+    #[inline]
+    fn reader_count(&self) -> usize {
+        self.raw_lock.reader_count()
+    }
+
+    #[inline]
+    fn is_write_locked(&self) -> bool {
+        self.raw_lock.is_write_locked()
+    }
+
+    #[inline]
+    fn waiter_count(&self) -> Option<usize> {
+        self.raw_lock.waiter_count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{PhaseFair, ReadoutRwLock, RwLock, SpinRwLock, TryRwLock};
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::thread::{scope, sleep};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn readout_test() {
+        let lock = SpinRwLock::<_, StdThreadFunctions, PhaseFair>::new(0usize);
+        assert!(!lock.is_locked());
+        let first = lock.try_read().expect("Could not read");
+        let second = lock.try_read().expect("Could not read");
+        assert!(lock.is_locked());
+        assert!(!lock.is_write_locked());
+        assert_eq!(lock.reader_count(), 2);
+        assert_eq!(lock.waiter_count(), Some(0));
+        scope(|scope| {
+            scope.spawn(|| drop(lock.write()));
+            while lock.waiter_count() != Some(1) {
+                sleep(Duration::from_millis(1));
+            }
+            drop((first, second));
+        });
+        let guard = lock.try_write().expect("Could not write");
+        assert!(lock.is_write_locked());
+        assert_eq!(lock.reader_count(), 0);
+        drop(guard);
+        assert!(!lock.is_locked());
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(feature = "alloc")]
+use crate::rw_lock::RawAsyncRwLock;
+use crate::rw_lock::{CustomReadGuard, CustomWriteGuard, RawRwLock};
+use crate::OnDrop;
+#[cfg(feature = "alloc")]
+use core::future::Future;
+#[cfg(feature = "alloc")]
+use core::mem::forget;
+
+impl<'a, T, R> CustomReadGuard<'a, T, R>
+where
+    R: RawRwLock,
+{
+    /// Removes this reader while `func` runs, re-adding it before returning.
+    /// The reader is re-added even if `func` panics.
+    pub fn unlocked<O>(guard: &mut Self, func: impl FnOnce() -> O) -> O {
+        let raw_lock = &guard.lock.raw_lock;
+        unsafe { raw_lock.remove_reader() }
+        let _relock = OnDrop(|| raw_lock.add_reader());
+        func()
+    }
+
+    /// Removes and re-adds this reader with [`RawRwLock::bump_reader`],
+    /// letting a waiting thread go first if the lock supports it.
+    pub fn bump(guard: &mut Self) {
+        unsafe { guard.lock.raw_lock.bump_reader() }
+    }
+}
+impl<'a, T, R> CustomWriteGuard<'a, T, R>
+where
+    R: RawRwLock,
+{
+    /// Removes this writer while `func` runs, re-adding it before returning.
+    /// The writer is re-added even if `func` panics.
+    pub fn unlocked<O>(guard: &mut Self, func: impl FnOnce() -> O) -> O {
+        let raw_lock = &guard.lock.raw_lock;
+        unsafe { raw_lock.remove_writer() }
+        let _relock = OnDrop(|| raw_lock.add_writer());
+        func()
+    }
+
+    /// Removes and re-adds this writer with [`RawRwLock::bump_writer`],
+    /// letting a waiting thread go first if the lock supports it.
+    pub fn bump(guard: &mut Self) {
+        unsafe { guard.lock.raw_lock.bump_writer() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a, T, R> CustomReadGuard<'a, T, R>
+where
+    R: RawAsyncRwLock,
+{
+    /// Removes this reader while `future` runs, re-adding it asynchronously
+    /// and returning the guard with the output. Takes the guard so nothing
+    /// is held or re-added if `future` panics or this is dropped early.
+    pub async fn unlocked_async<O>(guard: Self, future: impl Future<Output = O>) -> (Self, O) {
+        let lock = guard.lock;
+        forget(guard);
+        unsafe { lock.raw_lock.remove_reader() }
+        let out = future.await;
+        lock.raw_lock.add_reader_async().await;
+        (CustomReadGuard { lock }, out)
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a, T, R> CustomWriteGuard<'a, T, R>
+where
+    R: RawAsyncRwLock,
+{
+    /// Removes this writer while `future` runs, re-adding it asynchronously
+    /// and returning the guard with the output. Takes the guard so nothing
+    /// is held or re-added if `future` panics or this is dropped early.
+    pub async fn unlocked_async<O>(guard: Self, future: impl Future<Output = O>) -> (Self, O) {
+        let lock = guard.lock;
+        forget(guard);
+        unsafe { lock.raw_lock.remove_writer() }
+        let out = future.await;
+        lock.raw_lock.add_writer_async().await;
+        (CustomWriteGuard { lock }, out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{CustomReadGuard, CustomWriteGuard, RwLock, SpinRwLock, TryRwLock};
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn unlocked_test() {
+        let lock = SpinRwLock::<_, StdThreadFunctions>::new(0usize);
+        let mut write_guard = lock.write();
+        CustomWriteGuard::unlocked(&mut write_guard, || {
+            *lock.try_write().expect("Not unlocked") += 1
+        });
+        assert!(lock.try_read().is_none());
+        CustomWriteGuard::bump(&mut write_guard);
+        *write_guard += 1;
+        drop(write_guard);
+
+        let mut read_guard = lock.read();
+        CustomReadGuard::unlocked(&mut read_guard, || {
+            *lock.try_write().expect("Not unlocked") += 1
+        });
+        assert!(lock.try_write().is_none());
+        CustomReadGuard::bump(&mut read_guard);
+        assert_eq!(*read_guard, 3);
+    }
+}
@@ -0,0 +1,98 @@
+mod r#try;
+pub use r#try::*;
+
+use crate::rw_lock::{
+    CustomRwLock, CustomUpgradableReadGuard, CustomWriteGuard, RawRwLock, RwLock, UpgradeReadGuard,
+};
+use core::mem::ManuallyDrop;
+
+/// A raw rw lock that can block on an upgradable reader.
+///
+/// # Safety
+/// Implementors must uphold the requirements of [`RawTryUpgradableRwLock`].
+pub unsafe trait RawUpgradableRwLock: RawTryUpgradableRwLock + RawRwLock {
+    /// Blocks until the upgradable reader is added to this lock
+    fn add_upgradable(&self);
+    /// Blocks until the upgradable reader is changed to a writer.
+    ///
+    /// # Safety
+    /// Caller must ensure that this lock had an upgradable reader that was not
+    /// removed
+    unsafe fn upgrade_upgradable(&self);
+}
+/// An rwlock that can block on an upgradable read.
+pub trait UpgradableRwLock<'a>: TryUpgradableRwLock<'a> + RwLock<'a>
+where
+    Self::UpgradableGuard: UpgradeReadGuard<'a, Item = Self::Item, WriteGuard = Self::WriteGuard>,
+{
+    /// Gets the upgradable read, blocking until able.
+    fn upgradable_read(&'a self) -> Self::UpgradableGuard;
+}
+
+impl<'a, T, R> UpgradableRwLock<'a> for CustomRwLock<T, R>
+where
+    T: 'a,
+    R: RawUpgradableRwLock + 'a,
+{
+    fn upgradable_read(&'a self) -> Self::UpgradableGuard {
+        self.raw_lock.add_upgradable();
+        CustomUpgradableReadGuard { lock: self }
+    }
+}
+
+impl<'a, T, R> UpgradeReadGuard<'a> for CustomUpgradableReadGuard<'a, T, R>
+where
+    R: RawUpgradableRwLock,
+{
+    fn upgrade(self) -> Self::WriteGuard {
+        unsafe { self.lock.raw_lock.upgrade_upgradable() }
+        let self_manual = ManuallyDrop::new(self);
+        CustomWriteGuard {
+            lock: self_manual.lock,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "std")]
+    use crate::rw_lock::{
+        RwLock, SpinRwLock, TryRwLock, TryUpgradableRwLock, UpgradableRwLock, UpgradeReadGuard,
+    };
+    #[cfg(feature = "std")]
+    use crate::StdThreadFunctions;
+    #[cfg(feature = "std")]
+    use std::sync::Arc;
+    #[cfg(feature = "std")]
+    use std::thread::spawn;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn upgradable_read_test() {
+        let lock = Arc::new(SpinRwLock::<_, StdThreadFunctions>::new(0usize));
+
+        let upgradable = lock.upgradable_read();
+        let read_guard = lock.try_read().expect("Readers should coexist");
+        assert!(lock.try_upgradable_read().is_none());
+        assert!(lock.try_write().is_none());
+        drop(read_guard);
+        drop(upgradable.upgrade());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                spawn(move || {
+                    for _ in 0..100 {
+                        let guard = lock.upgradable_read();
+                        let value = *guard;
+                        *guard.upgrade() = value + 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Could not join");
+        }
+        assert_eq!(*lock.read(), 400);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::rw_lock::{
+    CustomRwLock, CustomUpgradableReadGuard, CustomWriteGuard, RawTryRwLock, TryRwLock,
+    TryUpgradeReadGuard,
+};
+use core::mem::ManuallyDrop;
+
+/// A raw rw lock that can hold a single upgradable reader alongside normal
+/// readers.
+///
+/// # Safety
+/// Implementors must ensure only one upgradable reader exists at a time and
+/// that it excludes writers like a normal reader.
+pub unsafe trait RawTryUpgradableRwLock: RawTryRwLock {
+    /// Tries to add the upgradable reader to the lock. Returns true if
+    /// successful.
+    fn try_add_upgradable(&self) -> bool;
+    /// Removes the upgradable reader from this lock.
+    ///
+    /// # Safety
+    /// Caller must ensure that this lock had an upgradable reader that was not
+    /// removed
+    unsafe fn remove_upgradable(&self);
+    /// Tries to upgrade the upgradable reader to a writer.
+    ///
+    /// # Safety
+    /// Caller must ensure that this lock had an upgradable reader that was not
+    /// removed
+    unsafe fn try_upgrade_upgradable(&self) -> bool;
+}
+/// An rwlock that can hand out a single upgradable read guard that coexists
+/// with normal readers. Because other upgradable readers are excluded
+/// upgrading cannot deadlock with another upgrade, as long as no normal reader
+/// is blocked in an upgrade through [`UpgradeRwLock`](crate::rw_lock::UpgradeRwLock)
+/// at the same time. Such a reader waits on the upgradable reader to leave
+/// while the upgradable reader waits on it.
+pub trait TryUpgradableRwLock<'a>: TryRwLock<'a> {
+    /// The guard for an upgradable read
+    type UpgradableGuard: TryUpgradeReadGuard<'a, Item = Self::Item, WriteGuard = Self::WriteGuard>;
+
+    /// Tries to get the upgradable read, returning [`None`] if cannot
+    /// immediately
+    fn try_upgradable_read(&'a self) -> Option<Self::UpgradableGuard>;
+}
+
+impl<'a, T, R> TryUpgradableRwLock<'a> for CustomRwLock<T, R>
+where
+    T: 'a,
+    R: RawTryUpgradableRwLock + 'a,
+{
+    type UpgradableGuard = CustomUpgradableReadGuard<'a, T, R>;
+
+    fn try_upgradable_read(&'a self) -> Option<Self::UpgradableGuard> {
+        match self.raw_lock.try_add_upgradable() {
+            true => Some(CustomUpgradableReadGuard { lock: self }),
+            false => None,
+        }
+    }
+}
+
+impl<'a, T, R> TryUpgradeReadGuard<'a> for CustomUpgradableReadGuard<'a, T, R>
+where
+    R: RawTryUpgradableRwLock,
+{
+    type Item = T;
+    type WriteGuard = CustomWriteGuard<'a, T, R>;
+
+    fn try_upgrade(self) -> Result<Self::WriteGuard, Self> {
+        match unsafe { self.lock.raw_lock.try_upgrade_upgradable() } {
+            true => {
+                let self_manual = ManuallyDrop::new(self);
+                Ok(CustomWriteGuard {
+                    lock: self_manual.lock,
+                })
+            }
+            false => Err(self),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! The background timer thread behind [`AsyncTimeFunctions`](crate::AsyncTimeFunctions) for [`StdThreadFunctions`].
+
+use crate::{OnDrop, StdThreadFunctions, ThreadParker, ThreadTimeoutParker};
+use simple_futures::complete_future::{CompleteFuture, CompleteFutureHandle};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::thread::Thread;
+use std::time::Instant;
+
+static TIMER: OnceLock<Timer> = OnceLock::new();
+
+struct Timer {
+    inner: Mutex<TimerInner>,
+    thread: Thread,
+}
+struct TimerInner {
+    /// Keyed by deadline then by a unique id so equal deadlines can coexist.
+    entries: BTreeMap<(Instant, u64), CompleteFutureHandle>,
+    next_id: u64,
+}
+
+fn timer() -> &'static Timer {
+    TIMER.get_or_init(|| Timer {
+        inner: Mutex::new(TimerInner {
+            entries: BTreeMap::new(),
+            next_id: 0,
+        }),
+        thread: std::thread::Builder::new()
+            .name("concurrency_traits timer".into())
+            .spawn(run)
+            .expect("Could not spawn timer thread")
+            .thread()
+            .clone(),
+    })
+}
+
+fn run() {
+    let timer = timer();
+    loop {
+        let next = {
+            let mut inner = timer.inner.lock().expect("Timer poisoned");
+            let now = Instant::now();
+            while inner
+                .entries
+                .first_key_value()
+                .is_some_and(|(&(end, _), _)| end <= now)
+            {
+                inner.entries.pop_first().unwrap().1.complete();
+            }
+            inner.entries.first_key_value().map(|(&(end, _), _)| end)
+        };
+        match next {
+            None => StdThreadFunctions::park(),
+            Some(end) => {
+                StdThreadFunctions::park_timeout(end.saturating_duration_since(Instant::now()))
+            }
+        }
+    }
+}
+
+/// Completes when `end` is reached. Wakes the timer thread if `end` is its new earliest deadline.
+/// The entry is removed if this is dropped before completing.
+pub(crate) async fn sleep_until(end: Instant) {
+    if Instant::now() >= end {
+        return;
+    }
+    let future = CompleteFuture::new();
+    let timer = timer();
+    let (key, earliest) = {
+        let mut inner = timer.inner.lock().expect("Timer poisoned");
+        let key = (end, inner.next_id);
+        inner.next_id += 1;
+        let earliest = inner
+            .entries
+            .first_key_value()
+            .is_none_or(|(&(first, _), _)| end < first);
+        inner.entries.insert(key, future.get_handle());
+        (key, earliest)
+    };
+    let _remove = OnDrop(|| {
+        timer
+            .inner
+            .lock()
+            .expect("Timer poisoned")
+            .entries
+            .remove(&key);
+    });
+    if earliest {
+        timer.thread.unpark();
+    }
+    future.await
+}